@@ -18,4 +18,9 @@ async fn main() {
     };
 
     println!("{:?}", image_object_vec[0]);
+
+    match client.save_image(&image_object_vec[0], "dog.png").await {
+        Ok(image_data) => println!("Saved the image ({:?}) to dog.png", image_data.format()),
+        Err(e) => println!("Error: {}", e),
+    }
 }
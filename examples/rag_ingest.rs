@@ -0,0 +1,35 @@
+use llamaedge::{
+    params::EmbeddingsParams,
+    rag::ingest::{IngestEvent, IngestOptions},
+    Client,
+};
+
+#[tokio::main]
+async fn main() {
+    const SERVER_BASE_URL: &str = "http://localhost:10086";
+
+    let client = Client::new(SERVER_BASE_URL).unwrap();
+
+    let params = EmbeddingsParams {
+        vdb_server_url: Some("http://localhost:6333".to_string()),
+        vdb_collection_name: Some("default".to_string()),
+        ..Default::default()
+    };
+    let options = IngestOptions::new().with_progress(|event| match event {
+        IngestEvent::Completed { path, chunks } => {
+            println!("Ingested {} ({} chunks)", path.display(), chunks)
+        }
+        IngestEvent::Failed { path, error } => {
+            println!("Failed to ingest {}: {}", path.display(), error)
+        }
+        _ => {}
+    });
+
+    match client.ingest(["tests/assets"], params, options).await {
+        Ok(summary) => println!(
+            "Indexed {} chunks into {}",
+            summary.chunks, summary.collection
+        ),
+        Err(e) => println!("Error: {}", e),
+    }
+}
@@ -6,7 +6,7 @@ async fn main() {
 
     let client = Client::new(SERVER_BASE_URL).unwrap();
 
-    let transcription_object = match client
+    let transcript = match client
         .transcribe(
             "tests/assets/test.wav",
             "en",
@@ -21,5 +21,5 @@ async fn main() {
         }
     };
 
-    println!("{}", transcription_object.text);
+    println!("{}", transcript.text());
 }
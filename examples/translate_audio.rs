@@ -6,7 +6,7 @@ async fn main() {
 
     let client = Client::new(SERVER_BASE_URL).unwrap();
 
-    let translation_object = match client
+    let translation = match client
         .translate(
             "tests/assets/test_zh.wav",
             "zh",
@@ -21,5 +21,5 @@ async fn main() {
         }
     };
 
-    println!("{}", translation_object.text);
+    println!("{}", translation.text());
 }
@@ -0,0 +1,164 @@
+//! Types for the audio transcription and translation APIs.
+
+pub mod long;
+pub mod subtitles;
+pub mod wav;
+
+use crate::error::LlamaEdgeError;
+use endpoints::audio::transcription::TranscriptionObject;
+use serde::{Deserialize, Serialize};
+use std::{fmt, str::FromStr};
+
+/// The format of the output of a transcription or a translation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TranscriptionFormat {
+    /// A JSON object carrying the text.
+    #[default]
+    Json,
+    /// Plain text.
+    Text,
+    /// A subtitle document in the SubRip format.
+    Srt,
+    /// A JSON object carrying the text together with the segment and word timestamps.
+    VerboseJson,
+    /// A subtitle document in the WebVTT format.
+    Vtt,
+}
+impl TranscriptionFormat {
+    /// Get the value of the format sent to the server.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TranscriptionFormat::Json => "json",
+            TranscriptionFormat::Text => "text",
+            TranscriptionFormat::Srt => "srt",
+            TranscriptionFormat::VerboseJson => "verbose_json",
+            TranscriptionFormat::Vtt => "vtt",
+        }
+    }
+}
+impl fmt::Display for TranscriptionFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+impl FromStr for TranscriptionFormat {
+    type Err = LlamaEdgeError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "json" => Ok(TranscriptionFormat::Json),
+            "text" => Ok(TranscriptionFormat::Text),
+            "srt" => Ok(TranscriptionFormat::Srt),
+            "verbose_json" => Ok(TranscriptionFormat::VerboseJson),
+            "vtt" => Ok(TranscriptionFormat::Vtt),
+            _ => Err(LlamaEdgeError::InvalidArgument(format!(
+                "Unsupported transcription format: {}. Supported formats are `json`, `text`, `srt`, `verbose_json` and `vtt`.",
+                s
+            ))),
+        }
+    }
+}
+
+/// The output of a transcription or a translation, in the requested [`TranscriptionFormat`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum Transcript {
+    /// The text of a `json` or `text` response.
+    Text(String),
+    /// A subtitle document in the SubRip format.
+    Srt(String),
+    /// A subtitle document in the WebVTT format.
+    Vtt(String),
+    /// The text together with the segment and word timestamps.
+    Verbose(VerboseTranscript),
+}
+impl Transcript {
+    /// Get the transcribed text. For subtitle documents, the whole document is returned.
+    pub fn text(&self) -> &str {
+        match self {
+            Transcript::Text(text) | Transcript::Srt(text) | Transcript::Vtt(text) => text,
+            Transcript::Verbose(verbose) => &verbose.text,
+        }
+    }
+
+    /// Get the segments and words with timestamps, if the transcript was requested in the `verbose_json` format.
+    pub fn verbose(&self) -> Option<&VerboseTranscript> {
+        match self {
+            Transcript::Verbose(verbose) => Some(verbose),
+            _ => None,
+        }
+    }
+}
+
+/// The output of a transcription or a translation in the `verbose_json` format.
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub struct VerboseTranscript {
+    /// The task performed, i.e. `transcribe` or `translate`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub task: Option<String>,
+    /// The language of the input audio.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub language: Option<String>,
+    /// The duration of the input audio, in seconds.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub duration: Option<f64>,
+    /// The transcribed text.
+    pub text: String,
+    /// The segments of the transcribed text.
+    #[serde(default)]
+    pub segments: Vec<TranscriptSegment>,
+    /// The words of the transcribed text. Only populated if the `word` timestamp granularity is requested.
+    #[serde(default)]
+    pub words: Vec<TranscriptWord>,
+}
+
+impl From<VerboseTranscript> for TranscriptionObject {
+    fn from(transcript: VerboseTranscript) -> Self {
+        TranscriptionObject {
+            text: transcript.text,
+        }
+    }
+}
+
+/// A segment of a [`VerboseTranscript`].
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub struct TranscriptSegment {
+    /// The index of the segment.
+    #[serde(default)]
+    pub id: u64,
+    /// The seek offset of the segment.
+    #[serde(default)]
+    pub seek: u64,
+    /// The start time of the segment, in seconds.
+    pub start: f64,
+    /// The end time of the segment, in seconds.
+    pub end: f64,
+    /// The text of the segment.
+    pub text: String,
+    /// The token ids of the text.
+    #[serde(default)]
+    pub tokens: Vec<i64>,
+    /// The sampling temperature used to generate the segment.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub temperature: Option<f64>,
+    /// The average log probability of the segment.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub avg_logprob: Option<f64>,
+    /// The compression ratio of the segment.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub compression_ratio: Option<f64>,
+    /// The probability that the segment contains no speech.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub no_speech_prob: Option<f64>,
+}
+
+/// A word of a [`VerboseTranscript`].
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub struct TranscriptWord {
+    /// The text of the word.
+    pub word: String,
+    /// The start time of the word, in seconds.
+    pub start: f64,
+    /// The end time of the word, in seconds.
+    pub end: f64,
+}
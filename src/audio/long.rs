@@ -0,0 +1,220 @@
+//! Transcription of recordings longer than the server can process in one request, used by [`Client::transcribe_long`](crate::Client::transcribe_long).
+
+use super::{TranscriptSegment, TranscriptWord, VerboseTranscript};
+use crate::error::LlamaEdgeError;
+use std::time::Duration;
+
+/// The maximum number of characters of the previous chunk's text sent as the prompt of the next chunk. Whisper models only use the last 224 tokens of the prompt.
+const PROMPT_MAX_CHARS: usize = 800;
+
+/// Options for splitting a long recording into windows transcribed separately.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LongTranscriptionOptions {
+    window: Duration,
+    overlap: Duration,
+    concurrency: usize,
+    carry_prompt: bool,
+}
+impl LongTranscriptionOptions {
+    /// Create options with the default settings: windows of 30 seconds overlapping by 1 second, 2 concurrent requests, and the text of the previous chunk carried forward as the prompt.
+    pub fn new() -> Self {
+        Self {
+            window: Duration::from_secs(30),
+            overlap: Duration::from_secs(1),
+            concurrency: 2,
+            carry_prompt: true,
+        }
+    }
+
+    /// Set the duration of each window.
+    pub fn with_window(mut self, window: Duration) -> Self {
+        self.window = window;
+        self
+    }
+
+    /// Set the duration of the overlap between two consecutive windows, so that words cut at the edge of a window are transcribed in full by one of them. It must be shorter than the window.
+    pub fn with_overlap(mut self, overlap: Duration) -> Self {
+        self.overlap = overlap;
+        self
+    }
+
+    /// Set the maximum number of windows transcribed concurrently. It only applies when the prompt is not carried forward, see [`LongTranscriptionOptions::with_carry_prompt`].
+    pub fn with_concurrency(mut self, concurrency: usize) -> Self {
+        self.concurrency = concurrency;
+        self
+    }
+
+    /// Enable or disable sending the text of the previous chunk as the `prompt` of the next one, for continuity of spelling and style across windows.
+    ///
+    /// Each chunk has to wait for the text of the previous one, so the windows are then transcribed one after the other whatever the concurrency. This keeps the requests, and so the transcript, the same from one run to the next.
+    pub fn with_carry_prompt(mut self, carry_prompt: bool) -> Self {
+        self.carry_prompt = carry_prompt;
+        self
+    }
+
+    /// Get the maximum number of windows transcribed concurrently.
+    pub fn concurrency(&self) -> usize {
+        self.concurrency
+    }
+
+    /// Get the number of windows actually transcribed concurrently: one when the prompt is carried forward, the configured concurrency otherwise.
+    pub(crate) fn effective_concurrency(&self) -> usize {
+        if self.carry_prompt {
+            1
+        } else {
+            self.concurrency
+        }
+    }
+
+    /// Check if the text of the previous chunk is sent as the prompt of the next one.
+    pub fn carry_prompt(&self) -> bool {
+        self.carry_prompt
+    }
+
+    /// Check that the options are consistent.
+    pub(crate) fn validate(&self) -> Result<(), LlamaEdgeError> {
+        if self.window.is_zero() {
+            return Err(LlamaEdgeError::InvalidArgument(
+                "The window cannot be zero".to_string(),
+            ));
+        }
+        if self.overlap >= self.window {
+            return Err(LlamaEdgeError::InvalidArgument(
+                "The overlap must be shorter than the window".to_string(),
+            ));
+        }
+        if self.concurrency == 0 {
+            return Err(LlamaEdgeError::InvalidArgument(
+                "The concurrency cannot be zero".to_string(),
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Split a recording of the given duration into overlapping windows.
+    pub(crate) fn windows(&self, duration: Duration) -> Vec<Window> {
+        let step = self.window - self.overlap;
+
+        let mut windows = Vec::new();
+        let mut start = Duration::ZERO;
+        loop {
+            let end = (start + self.window).min(duration);
+            windows.push(Window { start, end });
+            if end >= duration {
+                break;
+            }
+            start += step;
+        }
+
+        windows
+    }
+
+    /// Get the time range each window is responsible for. The overlap between two windows is split in the middle.
+    fn owned_ranges(&self, windows: &[Window]) -> Vec<(Duration, Duration)> {
+        let half_overlap = self.overlap / 2;
+        (0..windows.len())
+            .map(|i| {
+                let from = if i == 0 {
+                    Duration::ZERO
+                } else {
+                    windows[i].start + half_overlap
+                };
+                let to = match windows.get(i + 1) {
+                    Some(next) => next.start + half_overlap,
+                    None => Duration::MAX,
+                };
+                (from, to)
+            })
+            .collect()
+    }
+
+    /// Stitch the transcripts of the windows into one transcript.
+    ///
+    /// The timestamps of each chunk are shifted by the start of its window, and the segments and words falling in the overlap with a neighbouring window are kept only once. A chunk without segments contributes its whole text.
+    pub(crate) fn stitch(
+        &self,
+        windows: &[Window],
+        chunks: Vec<VerboseTranscript>,
+        duration: Duration,
+    ) -> VerboseTranscript {
+        let owned_ranges = self.owned_ranges(windows);
+
+        let mut transcript = VerboseTranscript {
+            duration: Some(duration.as_secs_f64()),
+            ..Default::default()
+        };
+        let mut texts = Vec::new();
+        for ((window, (from, to)), chunk) in windows.iter().zip(owned_ranges).zip(chunks) {
+            transcript.task = transcript.task.or(chunk.task);
+            transcript.language = transcript.language.or(chunk.language);
+
+            let offset = window.start.as_secs_f64();
+            // timestamps come from the server, so one too large for a `Duration` is not owned rather than a panic
+            let owns = |start: f64, end: f64| {
+                Duration::try_from_secs_f64(((start + end) / 2.0 + offset).max(0.0))
+                    .is_ok_and(|middle| from <= middle && middle < to)
+            };
+
+            if chunk.segments.is_empty() {
+                texts.push(chunk.text.trim().to_string());
+            }
+            for segment in chunk.segments {
+                if owns(segment.start, segment.end) {
+                    texts.push(segment.text.trim().to_string());
+                    transcript.segments.push(TranscriptSegment {
+                        id: transcript.segments.len() as u64,
+                        start: segment.start + offset,
+                        end: segment.end + offset,
+                        ..segment
+                    });
+                }
+            }
+            for word in chunk.words {
+                if owns(word.start, word.end) {
+                    transcript.words.push(TranscriptWord {
+                        start: word.start + offset,
+                        end: word.end + offset,
+                        ..word
+                    });
+                }
+            }
+        }
+
+        texts.retain(|text| !text.is_empty());
+        transcript.text = texts.join(" ");
+        transcript
+    }
+}
+impl Default for LongTranscriptionOptions {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// A window of a long recording.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct Window {
+    /// The start time of the window in the recording.
+    pub(crate) start: Duration,
+    /// The end time of the window in the recording.
+    pub(crate) end: Duration,
+}
+
+/// Get the end of a text, short enough to be sent as a prompt. The text is cut at a word boundary.
+pub(crate) fn prompt_from(text: &str) -> String {
+    let text = text.trim();
+    let char_count = text.chars().count();
+    if char_count <= PROMPT_MAX_CHARS {
+        return text.to_string();
+    }
+
+    let tail = text
+        .char_indices()
+        .nth(char_count - PROMPT_MAX_CHARS)
+        .map_or(text, |(i, _)| &text[i..]);
+    match tail.split_once(char::is_whitespace) {
+        Some((_, rest)) if !rest.trim().is_empty() => rest.trim().to_string(),
+        _ => tail.to_string(),
+    }
+}
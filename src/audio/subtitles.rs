@@ -0,0 +1,317 @@
+//! Subtitles in the SubRip (SRT) and WebVTT formats, as returned by the transcription and translation APIs.
+
+use super::{Transcript, TranscriptSegment};
+use crate::error::LlamaEdgeError;
+use std::time::Duration;
+
+/// A subtitle cue, i.e. a text displayed between a start and an end time.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Cue {
+    /// The time the cue is displayed from.
+    pub start: Duration,
+    /// The time the cue is displayed until.
+    pub end: Duration,
+    /// The text of the cue. Lines are separated by `\n`.
+    pub text: String,
+}
+impl Cue {
+    /// Create a new cue.
+    ///
+    /// # Arguments
+    ///
+    /// * `start` - The time the cue is displayed from.
+    ///
+    /// * `end` - The time the cue is displayed until.
+    ///
+    /// * `text` - The text of the cue.
+    pub fn new(start: Duration, end: Duration, text: impl Into<String>) -> Self {
+        Self {
+            start,
+            end,
+            text: text.into(),
+        }
+    }
+}
+
+/// A subtitle document, i.e. a list of cues ordered by start time.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Subtitles {
+    /// The cues of the document.
+    pub cues: Vec<Cue>,
+}
+impl Subtitles {
+    /// Create a document from the given cues. The cues are sorted by start time.
+    pub fn new(cues: Vec<Cue>) -> Self {
+        let mut subtitles = Self { cues };
+        subtitles.sort();
+        subtitles
+    }
+
+    /// Parse a document in the SubRip (SRT) format.
+    ///
+    /// # Arguments
+    ///
+    /// * `srt` - The SRT document.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the subtitles or an error if a cue is malformed.
+    pub fn parse_srt(srt: &str) -> Result<Self, LlamaEdgeError> {
+        let cues = parse_blocks(srt)
+            .into_iter()
+            .map(|block| parse_cue(&block, srt))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(Self::new(cues))
+    }
+
+    /// Parse a document in the WebVTT format. Comments, style and region blocks, and cue settings are skipped.
+    ///
+    /// # Arguments
+    ///
+    /// * `vtt` - The WebVTT document.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the subtitles or an error if the header is missing or a cue is malformed.
+    pub fn parse_vtt(vtt: &str) -> Result<Self, LlamaEdgeError> {
+        let mut blocks = parse_blocks(vtt).into_iter();
+
+        match blocks.next() {
+            Some(header) if header[0].starts_with("WEBVTT") => {}
+            _ => {
+                return Err(LlamaEdgeError::Decode {
+                    message: "The WebVTT document does not start with `WEBVTT`".to_string(),
+                    body: vtt.to_string(),
+                })
+            }
+        }
+
+        let cues = blocks
+            .filter(|block| {
+                !["NOTE", "STYLE", "REGION"]
+                    .iter()
+                    .any(|keyword| block[0].starts_with(keyword))
+            })
+            .map(|block| parse_cue(&block, vtt))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(Self::new(cues))
+    }
+
+    /// Create a document from the segments of a transcript in the `verbose_json` format.
+    ///
+    /// # Arguments
+    ///
+    /// * `segments` - The segments of the transcript.
+    pub fn from_segments(segments: &[TranscriptSegment]) -> Self {
+        let cues = segments
+            .iter()
+            .map(|segment| {
+                Cue::new(
+                    seconds_to_duration(segment.start),
+                    seconds_to_duration(segment.end),
+                    segment.text.trim(),
+                )
+            })
+            .collect();
+
+        Self::new(cues)
+    }
+
+    /// Render the document in the SubRip (SRT) format. The cues are numbered from `1`.
+    pub fn to_srt(&self) -> String {
+        let mut srt = String::new();
+        for (i, cue) in self.cues.iter().enumerate() {
+            if i > 0 {
+                srt.push('\n');
+            }
+            srt.push_str(&format!(
+                "{}\n{} --> {}\n{}\n",
+                i + 1,
+                format_timestamp(cue.start, ','),
+                format_timestamp(cue.end, ','),
+                cue.text
+            ));
+        }
+        srt
+    }
+
+    /// Render the document in the WebVTT format.
+    pub fn to_vtt(&self) -> String {
+        let mut vtt = String::from("WEBVTT\n");
+        for cue in &self.cues {
+            vtt.push_str(&format!(
+                "\n{} --> {}\n{}\n",
+                format_timestamp(cue.start, '.'),
+                format_timestamp(cue.end, '.'),
+                cue.text
+            ));
+        }
+        vtt
+    }
+
+    /// Get the text of all the cues, separated by spaces.
+    pub fn text(&self) -> String {
+        self.cues
+            .iter()
+            .map(|cue| cue.text.replace('\n', " "))
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    /// Delay all the cues by the given offset, e.g. the `offset_time` of a transcription of a part of a recording. Timestamps that would overflow are capped at the maximum [`Duration`].
+    pub fn shift(&mut self, offset: Duration) {
+        for cue in &mut self.cues {
+            cue.start = cue.start.saturating_add(offset);
+            cue.end = cue.end.saturating_add(offset);
+        }
+    }
+
+    /// Append the cues of another document, delayed by the given offset, e.g. the start time of the next chunk of a long recording. The cues are kept sorted by start time.
+    ///
+    /// # Arguments
+    ///
+    /// * `other` - The document to append.
+    ///
+    /// * `offset` - The offset to delay the cues of `other` by.
+    pub fn append(&mut self, mut other: Subtitles, offset: Duration) {
+        other.shift(offset);
+        self.cues.append(&mut other.cues);
+        self.sort();
+    }
+
+    /// Merge several documents into one, keeping the cues sorted by start time.
+    pub fn merge(documents: impl IntoIterator<Item = Subtitles>) -> Self {
+        let cues = documents
+            .into_iter()
+            .flat_map(|document| document.cues)
+            .collect();
+
+        Self::new(cues)
+    }
+
+    /// Sort the cues by start time, keeping the order of cues starting at the same time.
+    fn sort(&mut self) {
+        self.cues.sort_by_key(|cue| cue.start);
+    }
+}
+impl TryFrom<&Transcript> for Subtitles {
+    type Error = LlamaEdgeError;
+
+    /// Get the subtitles of a transcript in the `srt`, `vtt` or `verbose_json` format.
+    fn try_from(transcript: &Transcript) -> Result<Self, Self::Error> {
+        match transcript {
+            Transcript::Srt(srt) => Self::parse_srt(srt),
+            Transcript::Vtt(vtt) => Self::parse_vtt(vtt),
+            Transcript::Verbose(verbose) => Ok(Self::from_segments(&verbose.segments)),
+            Transcript::Text(_) => Err(LlamaEdgeError::InvalidArgument(
+                "A transcript in the `json` or `text` format has no timestamps".to_string(),
+            )),
+        }
+    }
+}
+
+/// Split a document into blocks of non-empty lines, separated by blank lines.
+fn parse_blocks(document: &str) -> Vec<Vec<&str>> {
+    let document = document.trim_start_matches('\u{feff}');
+
+    let mut blocks = Vec::new();
+    let mut block = Vec::new();
+    for line in document.lines() {
+        // `lines` strips `\n` and `\r\n`, but not a lone `\r`
+        let line = line.trim_end_matches('\r');
+        if line.trim().is_empty() {
+            if !block.is_empty() {
+                blocks.push(std::mem::take(&mut block));
+            }
+        } else {
+            block.push(line);
+        }
+    }
+    if !block.is_empty() {
+        blocks.push(block);
+    }
+
+    blocks
+}
+
+/// Parse a cue from its block of lines: an optional identifier, the timing line and the text.
+fn parse_cue(block: &[&str], document: &str) -> Result<Cue, LlamaEdgeError> {
+    let decode_error = |message: String| LlamaEdgeError::Decode {
+        message,
+        body: document.to_string(),
+    };
+
+    let timing_index = block
+        .iter()
+        .take(2)
+        .position(|line| line.contains("-->"))
+        .ok_or_else(|| decode_error(format!("Missing cue timing in: {}", block.join("\n"))))?;
+    let timing = block[timing_index];
+
+    let (start, rest) = timing
+        .split_once("-->")
+        .ok_or_else(|| decode_error(format!("Invalid cue timing: {}", timing)))?;
+    // the end timestamp may be followed by WebVTT cue settings
+    let end = rest.split_whitespace().next().unwrap_or_default();
+
+    let start = parse_timestamp(start.trim())
+        .ok_or_else(|| decode_error(format!("Invalid timestamp: {}", start.trim())))?;
+    let end =
+        parse_timestamp(end).ok_or_else(|| decode_error(format!("Invalid timestamp: {}", end)))?;
+
+    Ok(Cue::new(start, end, block[timing_index + 1..].join("\n")))
+}
+
+/// Parse a timestamp in the `HH:MM:SS,mmm` (SRT) or `[HH:]MM:SS.mmm` (WebVTT) format.
+fn parse_timestamp(timestamp: &str) -> Option<Duration> {
+    let (time, millis) = timestamp.split_once([',', '.'])?;
+    if millis.is_empty() || millis.len() > 3 || !millis.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    // `5` is 500 milliseconds, as in `00:00:01.5`
+    let millis = millis.parse::<u64>().ok()? * 10u64.pow(3 - millis.len() as u32);
+
+    let parts = time
+        .split(':')
+        .map(|part| part.trim().parse::<u64>().ok())
+        .collect::<Option<Vec<_>>>()?;
+    let (hours, minutes, seconds) = match parts[..] {
+        [hours, minutes, seconds] => (hours, minutes, seconds),
+        [minutes, seconds] => (0, minutes, seconds),
+        _ => return None,
+    };
+    if minutes >= 60 || seconds >= 60 {
+        return None;
+    }
+
+    // a huge hour field is rejected rather than overflowing
+    let millis = hours
+        .checked_mul(3_600_000)?
+        .checked_add(minutes * 60_000 + seconds * 1000 + millis)?;
+
+    Some(Duration::from_millis(millis))
+}
+
+/// Format a timestamp as `HH:MM:SS{separator}mmm`.
+fn format_timestamp(timestamp: Duration, separator: char) -> String {
+    let millis = timestamp.as_millis();
+    format!(
+        "{:02}:{:02}:{:02}{}{:03}",
+        millis / 3_600_000,
+        millis / 60_000 % 60,
+        millis / 1000 % 60,
+        separator,
+        millis % 1000
+    )
+}
+
+/// Convert a time in seconds, as found in transcription segments, to a duration. Negative and invalid values are clamped to zero.
+fn seconds_to_duration(seconds: f64) -> Duration {
+    if seconds.is_finite() && seconds > 0.0 {
+        Duration::from_millis((seconds * 1000.0).round() as u64)
+    } else {
+        Duration::ZERO
+    }
+}
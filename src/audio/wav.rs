@@ -0,0 +1,378 @@
+//! Inspection of WAV files, and conversion to the 16 kHz mono 16-bit PCM format expected by whisper backends.
+
+use crate::{error::LlamaEdgeError, upload::UploadFile};
+use std::{f64::consts::PI, time::Duration};
+
+/// The sample rate expected by whisper backends.
+pub const WHISPER_SAMPLE_RATE: u32 = 16_000;
+
+/// The `WAVE_FORMAT_PCM` format tag.
+const FORMAT_PCM: u16 = 0x0001;
+
+/// The `WAVE_FORMAT_IEEE_FLOAT` format tag.
+const FORMAT_IEEE_FLOAT: u16 = 0x0003;
+
+/// The `WAVE_FORMAT_EXTENSIBLE` format tag, whose actual format is given by a sub-format GUID.
+const FORMAT_EXTENSIBLE: u16 = 0xFFFE;
+
+/// The number of zero crossings of the sinc filter on each side of an output sample when resampling.
+const RESAMPLE_ZERO_CROSSINGS: f64 = 16.0;
+
+/// The encoding of the samples of a WAV file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SampleFormat {
+    /// Integer PCM samples.
+    Pcm,
+    /// IEEE floating-point samples.
+    Float,
+    /// Another encoding, such as A-law or ADPCM, identified by its format tag.
+    Other(u16),
+}
+
+/// The format and the duration of a WAV file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WavInfo {
+    /// The encoding of the samples.
+    pub sample_format: SampleFormat,
+    /// The number of channels.
+    pub channels: u16,
+    /// The number of frames per second.
+    pub sample_rate: u32,
+    /// The number of bits of a sample.
+    pub bits_per_sample: u16,
+    /// The number of frames, i.e. samples per channel.
+    pub frames: u64,
+    /// The duration of the audio.
+    pub duration: Duration,
+}
+impl WavInfo {
+    /// Check if the file is in the 16 kHz mono 16-bit PCM format expected by whisper backends.
+    pub fn is_whisper_compatible(&self) -> bool {
+        self.sample_format == SampleFormat::Pcm
+            && self.channels == 1
+            && self.sample_rate == WHISPER_SAMPLE_RATE
+            && self.bits_per_sample == 16
+    }
+}
+
+/// Read the header of a WAV file.
+///
+/// # Arguments
+///
+/// * `bytes` - The content of the WAV file.
+///
+/// # Returns
+///
+/// A `Result` containing the format and the duration of the file, or an error if it is not a valid WAV file.
+pub fn inspect(bytes: &[u8]) -> Result<WavInfo, LlamaEdgeError> {
+    Ok(Wav::parse(bytes)?.info())
+}
+
+/// Convert a WAV file to 16 kHz mono 16-bit PCM.
+///
+/// The channels are downmixed by averaging them, and the audio is resampled with a windowed sinc filter. A file already in this format is returned unchanged.
+///
+/// # Arguments
+///
+/// * `bytes` - The content of the WAV file.
+///
+/// # Returns
+///
+/// A `Result` containing the converted WAV file, or an error if it is not a valid WAV file or its samples are neither integer PCM nor floating-point.
+pub fn to_whisper_wav(bytes: &[u8]) -> Result<Vec<u8>, LlamaEdgeError> {
+    let wav = Wav::parse(bytes)?;
+    if wav.info().is_whisper_compatible() {
+        return Ok(bytes.to_vec());
+    }
+
+    let samples = resample(&wav.mono_samples()?, wav.sample_rate, WHISPER_SAMPLE_RATE);
+
+    let data = samples
+        .iter()
+        .flat_map(|sample| {
+            let sample = (sample * 32768.0).round().clamp(-32768.0, 32767.0) as i16;
+            sample.to_le_bytes()
+        })
+        .collect::<Vec<_>>();
+
+    Ok(encode(&pcm16_mono_fmt(WHISPER_SAMPLE_RATE), &data))
+}
+
+/// Convert a WAV file with [`to_whisper_wav`] on the blocking thread pool, so that resampling a long recording does not stall the async runtime.
+pub(crate) async fn to_whisper_wav_blocking(
+    bytes: impl AsRef<[u8]> + Send + 'static,
+) -> Result<Vec<u8>, LlamaEdgeError> {
+    tokio::task::spawn_blocking(move || to_whisper_wav(bytes.as_ref()))
+        .await
+        .map_err(|e| {
+            LlamaEdgeError::Operation(format!("Failed to convert the audio file: {}", e))
+        })?
+}
+
+/// Convert an audio file to the format expected by whisper backends before it is uploaded. Files that are not WAV files are left unchanged.
+///
+/// The file is read asynchronously, and converted on the blocking thread pool.
+pub(crate) async fn prepare_upload(file: UploadFile) -> Result<UploadFile, LlamaEdgeError> {
+    let mime_type = file.mime_type().map(|mime_type| mime_type.to_string());
+    let (bytes, filename) = file.read_all("audio file").await?;
+    if !is_wav(&bytes) {
+        let file = UploadFile::from_bytes(bytes, filename);
+        return Ok(match mime_type {
+            Some(mime_type) => file.with_mime_type(mime_type),
+            None => file,
+        });
+    }
+
+    let converted = to_whisper_wav_blocking(bytes).await?;
+    Ok(UploadFile::from_bytes(converted, filename).with_mime_type("audio/wav"))
+}
+
+/// Check if the content starts with the signature of a WAV file.
+fn is_wav(bytes: &[u8]) -> bool {
+    bytes.len() >= 12 && &bytes[0..4] == b"RIFF" && &bytes[8..12] == b"WAVE"
+}
+
+/// A WAV file, borrowing the `fmt ` chunk and the sample data from the underlying bytes.
+pub(crate) struct Wav<'a> {
+    /// The raw content of the `fmt ` chunk.
+    fmt: &'a [u8],
+    /// The encoding of the samples.
+    sample_format: SampleFormat,
+    /// The number of channels.
+    channels: u16,
+    /// The number of frames per second.
+    sample_rate: u32,
+    /// The number of bytes of a frame, i.e. one sample of every channel.
+    block_align: u16,
+    /// The number of bits of a sample.
+    bits_per_sample: u16,
+    /// The sample data.
+    data: &'a [u8],
+}
+impl<'a> Wav<'a> {
+    /// Parse a WAV file. Chunks other than `fmt ` and `data` are skipped.
+    pub(crate) fn parse(bytes: &'a [u8]) -> Result<Self, LlamaEdgeError> {
+        let invalid = |message: &str| LlamaEdgeError::InvalidArgument(message.to_string());
+
+        if !is_wav(bytes) {
+            return Err(invalid("The audio file is not a WAV file"));
+        }
+
+        let mut fmt = None;
+        let mut data = None;
+        let mut offset = 12;
+        while offset + 8 <= bytes.len() {
+            let id = &bytes[offset..offset + 4];
+            let size = u32::from_le_bytes(read_array(bytes, offset + 4)) as usize;
+            let start = offset + 8;
+            // the size of the `data` chunk of a WAV file written as a stream may be unknown, so clamp it to the bytes available
+            let end = start.saturating_add(size).min(bytes.len());
+
+            match id {
+                b"fmt " => fmt = Some(&bytes[start..end]),
+                b"data" => {
+                    data = Some(&bytes[start..end]);
+                    break;
+                }
+                _ => {}
+            }
+
+            // chunks are padded to an even size
+            offset = start.saturating_add(size).saturating_add(size % 2);
+        }
+
+        let fmt = fmt.ok_or_else(|| invalid("The WAV file has no `fmt ` chunk"))?;
+        let data = data.ok_or_else(|| invalid("The WAV file has no `data` chunk"))?;
+        if fmt.len() < 16 {
+            return Err(invalid("The `fmt ` chunk of the WAV file is truncated"));
+        }
+
+        let format_tag = match u16::from_le_bytes(read_array(fmt, 0)) {
+            // the sub-format GUID starts with the format tag
+            FORMAT_EXTENSIBLE if fmt.len() >= 26 => u16::from_le_bytes(read_array(fmt, 24)),
+            format_tag => format_tag,
+        };
+        let sample_format = match format_tag {
+            FORMAT_PCM => SampleFormat::Pcm,
+            FORMAT_IEEE_FLOAT => SampleFormat::Float,
+            format_tag => SampleFormat::Other(format_tag),
+        };
+        let channels = u16::from_le_bytes(read_array(fmt, 2));
+        let sample_rate = u32::from_le_bytes(read_array(fmt, 4));
+        let block_align = u16::from_le_bytes(read_array(fmt, 12));
+        let bits_per_sample = u16::from_le_bytes(read_array(fmt, 14));
+        if channels == 0 || sample_rate == 0 || block_align == 0 {
+            return Err(invalid("The WAV file has an invalid format"));
+        }
+
+        Ok(Self {
+            fmt,
+            sample_format,
+            channels,
+            sample_rate,
+            block_align,
+            bits_per_sample,
+            data,
+        })
+    }
+
+    /// Get the format and the duration of the file.
+    pub(crate) fn info(&self) -> WavInfo {
+        WavInfo {
+            sample_format: self.sample_format,
+            channels: self.channels,
+            sample_rate: self.sample_rate,
+            bits_per_sample: self.bits_per_sample,
+            frames: self.frames() as u64,
+            duration: self.duration(),
+        }
+    }
+
+    /// Get the number of frames.
+    pub(crate) fn frames(&self) -> usize {
+        self.data.len() / self.block_align as usize
+    }
+
+    /// Get the duration of the audio.
+    pub(crate) fn duration(&self) -> Duration {
+        Duration::from_secs_f64(self.frames() as f64 / self.sample_rate as f64)
+    }
+
+    /// Write the audio between the given times as a new WAV file with the same format.
+    pub(crate) fn slice(&self, start: Duration, end: Duration) -> Vec<u8> {
+        let frame_at = |time: Duration| {
+            ((time.as_secs_f64() * self.sample_rate as f64) as usize).min(self.frames())
+        };
+        let block_align = self.block_align as usize;
+        let data = &self.data[frame_at(start) * block_align..frame_at(end) * block_align];
+
+        encode(self.fmt, data)
+    }
+
+    /// Decode the samples and downmix the channels by averaging them. The samples are normalized to `[-1, 1]`.
+    fn mono_samples(&self) -> Result<Vec<f64>, LlamaEdgeError> {
+        let channels = self.channels as usize;
+        let sample_size = self.block_align as usize / channels;
+
+        let decode: fn(&[u8]) -> f64 = match (self.sample_format, sample_size) {
+            (SampleFormat::Pcm, 1) => |b| (b[0] as f64 - 128.0) / 128.0,
+            (SampleFormat::Pcm, 2) => |b| i16::from_le_bytes([b[0], b[1]]) as f64 / 32768.0,
+            (SampleFormat::Pcm, 3) => {
+                |b| i32::from_le_bytes([0, b[0], b[1], b[2]]) as f64 / 2147483648.0
+            }
+            (SampleFormat::Pcm, 4) => {
+                |b| i32::from_le_bytes([b[0], b[1], b[2], b[3]]) as f64 / 2147483648.0
+            }
+            (SampleFormat::Float, 4) => |b| f32::from_le_bytes([b[0], b[1], b[2], b[3]]) as f64,
+            (SampleFormat::Float, 8) => |b| f64::from_le_bytes(read_array(b, 0)),
+            _ => {
+                return Err(LlamaEdgeError::InvalidArgument(format!(
+                    "Unsupported WAV encoding: {:?} with {}-bit samples",
+                    self.sample_format, self.bits_per_sample
+                )))
+            }
+        };
+
+        let samples = self
+            .data
+            .chunks_exact(self.block_align as usize)
+            .map(|frame| {
+                frame
+                    .chunks_exact(sample_size)
+                    .take(channels)
+                    .map(decode)
+                    .sum::<f64>()
+                    / channels as f64
+            })
+            .collect();
+
+        Ok(samples)
+    }
+}
+
+/// Resample a signal with a Hann-windowed sinc filter. When downsampling, the cutoff frequency is lowered to the new Nyquist frequency to avoid aliasing.
+fn resample(samples: &[f64], from_rate: u32, to_rate: u32) -> Vec<f64> {
+    if from_rate == to_rate || samples.is_empty() {
+        return samples.to_vec();
+    }
+
+    let ratio = from_rate as f64 / to_rate as f64;
+    let cutoff = (1.0 / ratio).min(1.0);
+    let half_width = RESAMPLE_ZERO_CROSSINGS / cutoff;
+    let output_len = ((samples.len() as f64) / ratio).round() as usize;
+
+    (0..output_len)
+        .map(|n| {
+            let center = n as f64 * ratio;
+            let first = (center - half_width).ceil().max(0.0) as usize;
+            let last = ((center + half_width).floor() as usize).min(samples.len() - 1);
+
+            let mut sum = 0.0;
+            let mut weights = 0.0;
+            for (k, sample) in samples.iter().enumerate().take(last + 1).skip(first) {
+                let x = k as f64 - center;
+                let window = 0.5 + 0.5 * (PI * x / half_width).cos();
+                let weight = cutoff * sinc(cutoff * x) * window;
+                sum += sample * weight;
+                weights += weight;
+            }
+
+            // normalizing by the sum of the weights keeps the gain at 1 near the edges of the signal
+            if weights.abs() > f64::EPSILON {
+                sum / weights
+            } else {
+                0.0
+            }
+        })
+        .collect()
+}
+
+/// The normalized sinc function.
+fn sinc(x: f64) -> f64 {
+    if x.abs() < 1e-9 {
+        1.0
+    } else {
+        (PI * x).sin() / (PI * x)
+    }
+}
+
+/// Create the `fmt ` chunk of a mono 16-bit PCM file.
+fn pcm16_mono_fmt(sample_rate: u32) -> Vec<u8> {
+    let mut fmt = Vec::with_capacity(16);
+    fmt.extend_from_slice(&FORMAT_PCM.to_le_bytes());
+    fmt.extend_from_slice(&1u16.to_le_bytes());
+    fmt.extend_from_slice(&sample_rate.to_le_bytes());
+    fmt.extend_from_slice(&(sample_rate * 2).to_le_bytes());
+    fmt.extend_from_slice(&2u16.to_le_bytes());
+    fmt.extend_from_slice(&16u16.to_le_bytes());
+    fmt
+}
+
+/// Write a WAV file made of the given `fmt ` chunk and sample data.
+pub(crate) fn encode(fmt: &[u8], data: &[u8]) -> Vec<u8> {
+    let fmt_padding = fmt.len() % 2;
+    let data_padding = data.len() % 2;
+    let riff_size = 4 + 8 + fmt.len() + fmt_padding + 8 + data.len() + data_padding;
+
+    let mut bytes = Vec::with_capacity(8 + riff_size);
+    bytes.extend_from_slice(b"RIFF");
+    bytes.extend_from_slice(&(riff_size as u32).to_le_bytes());
+    bytes.extend_from_slice(b"WAVE");
+    bytes.extend_from_slice(b"fmt ");
+    bytes.extend_from_slice(&(fmt.len() as u32).to_le_bytes());
+    bytes.extend_from_slice(fmt);
+    bytes.resize(bytes.len() + fmt_padding, 0);
+    bytes.extend_from_slice(b"data");
+    bytes.extend_from_slice(&(data.len() as u32).to_le_bytes());
+    bytes.extend_from_slice(data);
+    bytes.resize(bytes.len() + data_padding, 0);
+
+    bytes
+}
+
+/// Read a fixed number of bytes at the given offset. The caller checks the bounds.
+fn read_array<const N: usize>(bytes: &[u8], offset: usize) -> [u8; N] {
+    let mut array = [0u8; N];
+    array.copy_from_slice(&bytes[offset..offset + N]);
+    array
+}
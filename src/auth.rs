@@ -0,0 +1,71 @@
+//! Authentication for LlamaEdge API servers deployed behind a gateway.
+
+use std::fmt;
+
+/// The environment variable read for the API key if none is set on the client builder.
+pub const API_KEY_ENV_VAR: &str = "LLAMAEDGE_API_KEY";
+
+/// Provider of the bearer token sent in the `Authorization` header.
+///
+/// The provider is queried before every request, so an implementation can rotate the token at runtime. Any `Fn() -> Option<String>` closure is also an `AuthProvider`.
+pub trait AuthProvider: Send + Sync {
+    /// Get the current bearer token. Returns `None` to send the request without an `Authorization` header.
+    fn bearer_token(&self) -> Option<String>;
+}
+impl<F> AuthProvider for F
+where
+    F: Fn() -> Option<String> + Send + Sync,
+{
+    fn bearer_token(&self) -> Option<String> {
+        self()
+    }
+}
+
+/// A fixed API key.
+#[derive(Clone)]
+pub struct ApiKey(String);
+impl ApiKey {
+    /// Create a new API key.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The API key.
+    pub fn new(key: impl Into<String>) -> Self {
+        Self(key.into())
+    }
+
+    /// Read the API key from the `LLAMAEDGE_API_KEY` environment variable.
+    ///
+    /// # Returns
+    ///
+    /// The API key, or `None` if the variable is unset or empty.
+    pub fn from_env() -> Option<Self> {
+        Self::from_lookup(|name| std::env::var(name).ok())
+    }
+
+    /// Read the API key from the `LLAMAEDGE_API_KEY` variable of the given lookup, e.g. a configuration file loaded into a map. [`ApiKey::from_env`] looks up the process environment.
+    ///
+    /// # Arguments
+    ///
+    /// * `lookup` - The function returning the value of a variable from its name.
+    ///
+    /// # Returns
+    ///
+    /// The API key, or `None` if the variable is unset or empty.
+    pub fn from_lookup(lookup: impl FnOnce(&str) -> Option<String>) -> Option<Self> {
+        match lookup(API_KEY_ENV_VAR) {
+            Some(key) if !key.trim().is_empty() => Some(Self::new(key.trim())),
+            _ => None,
+        }
+    }
+}
+impl AuthProvider for ApiKey {
+    fn bearer_token(&self) -> Option<String> {
+        Some(self.0.clone())
+    }
+}
+impl fmt::Debug for ApiKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("ApiKey(***)")
+    }
+}
@@ -0,0 +1,693 @@
+//! A blocking client for the LlamaEdge API, for applications that do not use an async runtime.
+//!
+//! The blocking client wraps the async [`Client`](crate::Client) and drives it on a runtime it owns, so both clients share the same configuration, retry and error handling.
+//!
+//! **Note:** The blocking client must not be used, or dropped, within an async runtime, since blocking the thread of a runtime panics.
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//! use endpoints::chat::{
+//!     ChatCompletionRequestMessage, ChatCompletionUserMessage, ChatCompletionUserMessageContent,
+//! };
+//! use llamaedge::{blocking::Client, params::ChatParams};
+//!
+//! let client = Client::new("http://localhost:8080").unwrap();
+//!
+//! let messages = vec![ChatCompletionRequestMessage::User(
+//!     ChatCompletionUserMessage::new(
+//!         ChatCompletionUserMessageContent::Text("What is the capital of France?".to_string()),
+//!         None,
+//!     ),
+//! )];
+//!
+//! for chunk in client.chat_stream(&messages, &ChatParams::default()).unwrap() {
+//!     let chunk = chunk.unwrap();
+//!     if let Some(content) = chunk.choices.first().and_then(|c| c.delta.content.as_ref()) {
+//!         print!("{}", content);
+//!     }
+//! }
+//! ```
+
+#[cfg(feature = "audio")]
+use crate::{
+    audio::{long::LongTranscriptionOptions, Transcript, VerboseTranscript},
+    params::{TranscriptionParams, TranslationParams},
+};
+use crate::{
+    endpoint::Endpoint,
+    error::LlamaEdgeError,
+    params::{ChatParams, EmbeddingsParams},
+    stream::ChatCompletionStream,
+    tools::{ToolRegistry, ToolRun},
+    upload::UploadFile,
+    ClientBuilder,
+};
+#[cfg(feature = "image")]
+use crate::{
+    image::{ImageData, ImageInput},
+    params::{ImageCreateParams, ImageEditParams},
+};
+#[cfg(feature = "rag")]
+use crate::{
+    params::RagChatParams,
+    rag::{
+        ingest::{IngestOptions, IngestSummary},
+        RagChatCompletion, RagChatStream,
+    },
+};
+use bytes::Bytes;
+#[cfg(feature = "image")]
+use endpoints::images::ImageObject;
+use endpoints::{
+    chat::{ChatCompletionChunk, ChatCompletionObject, ChatCompletionRequestMessage},
+    embeddings::{EmbeddingsResponse, InputText},
+    files::{DeleteFileStatus, FileObject},
+    models::Model,
+};
+#[cfg(feature = "rag")]
+use endpoints::{embeddings::ChunksResponse, rag::RetrieveObject};
+use futures::StreamExt;
+use std::{fmt, future::Future, path::Path, sync::Arc};
+use tokio::runtime::Runtime;
+use url::Url;
+
+/// Blocking client for the LlamaEdge API.
+///
+/// The methods mirror those of the async [`Client`](crate::Client). Cloning the client is cheap and shares the same connection pool and runtime.
+#[derive(Clone)]
+pub struct Client {
+    inner: crate::Client,
+    runtime: Arc<Runtime>,
+}
+impl Client {
+    /// Create a new blocking client with the default settings.
+    ///
+    /// # Arguments
+    ///
+    /// * `server_base_url` - The base URL of the LlamaEdge API server.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the client or an error.
+    pub fn new(server_base_url: impl AsRef<str>) -> Result<Self, LlamaEdgeError> {
+        ClientBuilder::new(server_base_url).build_blocking()
+    }
+
+    /// Create a builder to configure the client. Call [`ClientBuilder::build_blocking`] to get a blocking client.
+    ///
+    /// # Arguments
+    ///
+    /// * `server_base_url` - The base URL of the LlamaEdge API server.
+    pub fn builder(server_base_url: impl AsRef<str>) -> ClientBuilder {
+        ClientBuilder::new(server_base_url)
+    }
+
+    /// Create a blocking client from the settings of a builder.
+    ///
+    /// The HTTP client is built for this client alone, so that its connection pool is only used from the runtime of this client. Connections are bound to the runtime that opened them.
+    pub(crate) fn from_builder(builder: ClientBuilder) -> Result<Self, LlamaEdgeError> {
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .map_err(|e| {
+                LlamaEdgeError::Operation(format!("Failed to create the runtime: {}", e))
+            })?;
+        let inner = runtime.block_on(async move { builder.build() })?;
+
+        Ok(Self {
+            inner,
+            runtime: Arc::new(runtime),
+        })
+    }
+
+    /// Get the base URL of the LlamaEdge API server.
+    pub fn server_base_url(&self) -> &Url {
+        self.inner.server_base_url()
+    }
+
+    /// Get the API version segment inserted between the base URL and the endpoint paths.
+    pub fn api_version(&self) -> &str {
+        self.inner.api_version()
+    }
+
+    /// Get the full URL of an endpoint.
+    ///
+    /// # Arguments
+    ///
+    /// * `endpoint` - The endpoint.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the URL or an error.
+    pub fn endpoint_url(&self, endpoint: Endpoint) -> Result<Url, LlamaEdgeError> {
+        self.inner.endpoint_url(endpoint)
+    }
+
+    /// Run a future of the async client to completion on the runtime of this client.
+    fn block_on<F: Future>(&self, future: F) -> F::Output {
+        self.runtime.block_on(future)
+    }
+
+    /// Send a chat completion request.
+    ///
+    /// # Arguments
+    ///
+    /// * `chat_history` - The chat history including the latest user message.
+    ///
+    /// * `params` - The parameters for the chat completion.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the content of the first choice or an error.
+    pub fn chat(
+        &self,
+        chat_history: &[ChatCompletionRequestMessage],
+        params: &ChatParams,
+    ) -> Result<String, LlamaEdgeError> {
+        self.block_on(self.inner.chat(chat_history, params))
+    }
+
+    /// Send a chat completion request and return the full chat completion object.
+    ///
+    /// # Arguments
+    ///
+    /// * `chat_history` - The chat history including the latest user message.
+    ///
+    /// * `params` - The parameters for the chat completion.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the chat completion object or an error.
+    pub fn chat_completion(
+        &self,
+        chat_history: &[ChatCompletionRequestMessage],
+        params: &ChatParams,
+    ) -> Result<ChatCompletionObject, LlamaEdgeError> {
+        self.block_on(self.inner.chat_completion(chat_history, params))
+    }
+
+    /// Send a chat completion request with streaming.
+    ///
+    /// # Arguments
+    ///
+    /// * `chat_history` - The chat history including the latest user message.
+    ///
+    /// * `params` - The parameters for the chat completion.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing an iterator over the chat completion chunks or an error. Each call to `next` blocks until the next chunk has been received.
+    pub fn chat_stream(
+        &self,
+        chat_history: &[ChatCompletionRequestMessage],
+        params: &ChatParams,
+    ) -> Result<ChatCompletionIter, LlamaEdgeError> {
+        let inner = self.block_on(self.inner.chat_stream(chat_history, params))?;
+
+        Ok(ChatCompletionIter {
+            inner,
+            runtime: self.runtime.clone(),
+        })
+    }
+
+    /// Send a chat completion request and execute the tool calls generated by the model until it produces a final answer. See [`Client::run_with_tools`](crate::Client::run_with_tools).
+    ///
+    /// # Arguments
+    ///
+    /// * `chat_history` - The chat history including the latest user message.
+    ///
+    /// * `params` - The parameters for the chat completion.
+    ///
+    /// * `registry` - The registry of the functions the model may call.
+    ///
+    /// * `max_iterations` - The maximum number of chat completion requests to send.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the final chat completion together with the extended chat history, or an error.
+    pub fn run_with_tools(
+        &self,
+        chat_history: &[ChatCompletionRequestMessage],
+        params: &ChatParams,
+        registry: &ToolRegistry,
+        max_iterations: usize,
+    ) -> Result<ToolRun, LlamaEdgeError> {
+        self.block_on(
+            self.inner
+                .run_with_tools(chat_history, params, registry, max_iterations),
+        )
+    }
+
+    /// Upload a file to the server.
+    ///
+    /// # Arguments
+    ///
+    /// * `file` - The path to the file to upload, or an [`UploadFile`] to send it from memory or an async reader, or to set its content type explicitly.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the file object or an error.
+    pub fn upload_file(&self, file: impl Into<UploadFile>) -> Result<FileObject, LlamaEdgeError> {
+        self.block_on(self.inner.upload_file(file))
+    }
+
+    /// List the files uploaded to the server.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the list of file objects or an error.
+    pub fn list_files(&self) -> Result<Vec<FileObject>, LlamaEdgeError> {
+        self.block_on(self.inner.list_files())
+    }
+
+    /// Retrieve the information of an uploaded file.
+    ///
+    /// # Arguments
+    ///
+    /// * `file_id` - The id of the file.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the file object or an error.
+    pub fn retrieve_file(&self, file_id: impl AsRef<str>) -> Result<FileObject, LlamaEdgeError> {
+        self.block_on(self.inner.retrieve_file(file_id))
+    }
+
+    /// Retrieve the content of an uploaded file.
+    ///
+    /// # Arguments
+    ///
+    /// * `file_id` - The id of the file.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the content of the file or an error.
+    pub fn retrieve_file_content(&self, file_id: impl AsRef<str>) -> Result<Bytes, LlamaEdgeError> {
+        self.block_on(self.inner.retrieve_file_content(file_id))
+    }
+
+    /// Download the content of an uploaded file to the given path.
+    ///
+    /// # Arguments
+    ///
+    /// * `file_id` - The id of the file.
+    ///
+    /// * `path` - The path to write the content to. An existing file is overwritten.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the number of bytes written or an error.
+    pub fn download_file(
+        &self,
+        file_id: impl AsRef<str>,
+        path: impl AsRef<Path>,
+    ) -> Result<u64, LlamaEdgeError> {
+        self.block_on(self.inner.download_file(file_id, path))
+    }
+
+    /// Delete an uploaded file from the server.
+    ///
+    /// # Arguments
+    ///
+    /// * `file_id` - The id of the file.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the deletion status or an error.
+    pub fn delete_file(
+        &self,
+        file_id: impl AsRef<str>,
+    ) -> Result<DeleteFileStatus, LlamaEdgeError> {
+        self.block_on(self.inner.delete_file(file_id))
+    }
+
+    /// List all available models.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the list of models or an error.
+    pub fn models(&self) -> Result<Vec<Model>, LlamaEdgeError> {
+        self.block_on(self.inner.models())
+    }
+
+    /// Compute embeddings for a given input.
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - The input to compute embeddings for.
+    ///
+    /// * `params` - The parameters for the embeddings.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the embeddings or an error.
+    pub fn embeddings(
+        &self,
+        input: InputText,
+        params: EmbeddingsParams,
+    ) -> Result<EmbeddingsResponse, LlamaEdgeError> {
+        self.block_on(self.inner.embeddings(input, params))
+    }
+
+    /// Transcribe an audio file.
+    ///
+    /// # Arguments
+    ///
+    /// * `audio_file` - The path to the audio file to transcribe, or an [`UploadFile`] to send it from memory or an async reader, or to set its content type explicitly.
+    ///
+    /// * `spoken_language` - The language of the audio file. The language should be in [ISO-639-1](https://en.wikipedia.org/wiki/List_of_ISO_639-1_codes) format. For example, "en" for English, "zh" for Chinese, "ja" for Japanese, etc.
+    ///
+    /// * `params` - The parameters for the transcription.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the transcript in the format requested by `params.response_format`, or an error.
+    #[cfg(feature = "audio")]
+    pub fn transcribe(
+        &self,
+        audio_file: impl Into<UploadFile>,
+        spoken_language: impl AsRef<str>,
+        params: TranscriptionParams,
+    ) -> Result<Transcript, LlamaEdgeError> {
+        self.block_on(self.inner.transcribe(audio_file, spoken_language, params))
+    }
+
+    /// Transcribe a WAV recording longer than the server can process in one request. See [`Client::transcribe_long`](crate::Client::transcribe_long).
+    ///
+    /// # Arguments
+    ///
+    /// * `audio_file` - The WAV file to transcribe.
+    ///
+    /// * `spoken_language` - The language of the audio file. The language should be in [ISO-639-1](https://en.wikipedia.org/wiki/List_of_ISO_639-1_codes) format.
+    ///
+    /// * `params` - The parameters for the transcription of each window. `response_format`, `offset_time` and `duration` are ignored.
+    ///
+    /// * `options` - The options for splitting the recording.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the stitched transcript or an error. An error is returned if the recording is empty, or if the transcription of any window fails.
+    #[cfg(feature = "audio")]
+    pub fn transcribe_long(
+        &self,
+        audio_file: impl AsRef<Path>,
+        spoken_language: impl AsRef<str>,
+        params: TranscriptionParams,
+        options: LongTranscriptionOptions,
+    ) -> Result<VerboseTranscript, LlamaEdgeError> {
+        self.block_on(
+            self.inner
+                .transcribe_long(audio_file, spoken_language, params, options),
+        )
+    }
+
+    /// Translate an audio file.
+    ///
+    /// # Arguments
+    ///
+    /// * `audio_file` - The path to the audio file to translate, or an [`UploadFile`] to send it from memory or an async reader, or to set its content type explicitly.
+    ///
+    /// * `spoken_language` - The language of the audio file. The language should be in [ISO-639-1](https://en.wikipedia.org/wiki/List_of_ISO_639-1_codes) format. For example, "en" for English, "zh" for Chinese, "ja" for Japanese, etc.
+    ///
+    /// * `params` - The parameters for the translation.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the translation in the format requested by `params.response_format`, or an error.
+    #[cfg(feature = "audio")]
+    pub fn translate(
+        &self,
+        audio_file: impl Into<UploadFile>,
+        spoken_language: impl AsRef<str>,
+        params: TranslationParams,
+    ) -> Result<Transcript, LlamaEdgeError> {
+        self.block_on(self.inner.translate(audio_file, spoken_language, params))
+    }
+
+    /// Create an image with the given prompt.
+    ///
+    /// # Arguments
+    ///
+    /// * `prompt` - The prompt for the image.
+    ///
+    /// * `params` - The parameters for the image creation.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the list of images or an error.
+    #[cfg(feature = "image")]
+    pub fn create_image(
+        &self,
+        prompt: impl AsRef<str>,
+        params: ImageCreateParams,
+    ) -> Result<Vec<ImageObject>, LlamaEdgeError> {
+        self.block_on(self.inner.create_image(prompt, params))
+    }
+
+    /// Edit the given image with the given prompt.
+    ///
+    /// # Arguments
+    ///
+    /// * `image` - The image to edit: the path to a local file, its content held in memory, [`UploadFile`](crate::upload::UploadFile) such as one read from an async reader, or a file previously uploaded with [`Client::upload_file`].
+    ///
+    /// * `prompt` - The prompt for the image edit.
+    ///
+    /// * `params` - The parameters for the image edit.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the list of images or an error. If the dimensions of the mask differ from those of the image, an error is returned before the request is sent.
+    #[cfg(feature = "image")]
+    pub fn edit_image(
+        &self,
+        image: impl Into<ImageInput>,
+        prompt: impl AsRef<str>,
+        params: ImageEditParams,
+    ) -> Result<Vec<ImageObject>, LlamaEdgeError> {
+        self.block_on(self.inner.edit_image(image, prompt, params))
+    }
+
+    /// Create variations of the given image.
+    ///
+    /// Set `params.n` to get several variations in one request, and `params.seed` to get the same variations for the same image.
+    ///
+    /// # Arguments
+    ///
+    /// * `image` - The image to vary: the path to a local file, its content held in memory, [`UploadFile`](crate::upload::UploadFile) such as one read from an async reader, or a file previously uploaded with [`Client::upload_file`].
+    ///
+    /// * `params` - The parameters for the variations. `mask` is ignored.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the list of images or an error.
+    #[cfg(feature = "image")]
+    pub fn create_image_variation(
+        &self,
+        image: impl Into<ImageInput>,
+        params: ImageEditParams,
+    ) -> Result<Vec<ImageObject>, LlamaEdgeError> {
+        self.block_on(self.inner.create_image_variation(image, params))
+    }
+
+    /// Generate an image from a source image and a prompt (img2img).
+    ///
+    /// The whole source image is regenerated, following the prompt. This is sent to the image edit endpoint without a mask, which the server handles as image-to-image generation.
+    ///
+    /// # Arguments
+    ///
+    /// * `image` - The source image: the path to a local file, its content held in memory, [`UploadFile`](crate::upload::UploadFile) such as one read from an async reader, or a file previously uploaded with [`Client::upload_file`].
+    ///
+    /// * `prompt` - The prompt for the generation.
+    ///
+    /// * `strength` - How much the source image is noised before it is regenerated, between 0 and 1. Low values keep the output close to the source image, and `1.0` ignores it. It overrides `params.strength`.
+    ///
+    /// * `params` - The parameters for the generation. `mask` is ignored.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the list of images or an error.
+    #[cfg(feature = "image")]
+    pub fn image_to_image(
+        &self,
+        image: impl Into<ImageInput>,
+        prompt: impl AsRef<str>,
+        strength: f32,
+        params: ImageEditParams,
+    ) -> Result<Vec<ImageObject>, LlamaEdgeError> {
+        self.block_on(self.inner.image_to_image(image, prompt, strength, params))
+    }
+
+    /// Get the raw content of an image returned by [`Client::create_image`] or [`Client::edit_image`].
+    ///
+    /// Images returned in the `b64_json` format are decoded. Images returned in the `url` format are downloaded with this client. The configured credentials are sent only if the URL is on the same origin as the server base URL.
+    ///
+    /// # Arguments
+    ///
+    /// * `image` - The image returned by the server.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the content of the image and its detected format, or an error.
+    #[cfg(feature = "image")]
+    pub fn image_bytes(&self, image: &ImageObject) -> Result<ImageData, LlamaEdgeError> {
+        self.block_on(self.inner.image_bytes(image))
+    }
+
+    /// Save an image returned by [`Client::create_image`] or [`Client::edit_image`] to the given path, as is. See [`Client::image_bytes`] for how its content is obtained.
+    ///
+    /// # Arguments
+    ///
+    /// * `image` - The image returned by the server.
+    ///
+    /// * `path` - The path to save the image to. The file is overwritten if it exists.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the content of the image and its detected format, or an error.
+    #[cfg(feature = "image")]
+    pub fn save_image(
+        &self,
+        image: &ImageObject,
+        path: impl AsRef<Path>,
+    ) -> Result<ImageData, LlamaEdgeError> {
+        self.block_on(self.inner.save_image(image, path))
+    }
+
+    /// Retrieve the context from the VectorDB server.
+    ///
+    /// # Arguments
+    ///
+    /// * `chat_history` - The chat history.
+    ///
+    /// * `params` - The parameters for the retrieval.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the retrieved context or an error.
+    #[cfg(feature = "rag")]
+    pub fn rag_retrieve_context(
+        &self,
+        chat_history: &[ChatCompletionRequestMessage],
+        params: RagChatParams,
+    ) -> Result<Vec<RetrieveObject>, LlamaEdgeError> {
+        self.block_on(self.inner.rag_retrieve_context(chat_history, params))
+    }
+
+    /// Send a RAG chat completion request. See [`Client::rag_chat`](crate::Client::rag_chat).
+    ///
+    /// # Arguments
+    ///
+    /// * `chat_history` - The chat history including the latest user message.
+    ///
+    /// * `params` - The parameters for the retrieval and the chat completion.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the chat completion together with the retrieved context, or an error.
+    #[cfg(feature = "rag")]
+    pub fn rag_chat(
+        &self,
+        chat_history: &[ChatCompletionRequestMessage],
+        params: RagChatParams,
+    ) -> Result<RagChatCompletion, LlamaEdgeError> {
+        self.block_on(self.inner.rag_chat(chat_history, params))
+    }
+
+    /// Send a RAG chat completion request with streaming. See [`Client::rag_chat_stream`](crate::Client::rag_chat_stream).
+    ///
+    /// # Arguments
+    ///
+    /// * `chat_history` - The chat history including the latest user message.
+    ///
+    /// * `params` - The parameters for the retrieval and the chat completion.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the retrieved context together with an iterator over the chat completion chunks, or an error. Each call to `next` blocks until the next chunk has been received.
+    #[cfg(feature = "rag")]
+    pub fn rag_chat_stream(
+        &self,
+        chat_history: &[ChatCompletionRequestMessage],
+        params: RagChatParams,
+    ) -> Result<RagChatStream<ChatCompletionIter>, LlamaEdgeError> {
+        let RagChatStream { stream, sources } =
+            self.block_on(self.inner.rag_chat_stream(chat_history, params))?;
+
+        Ok(RagChatStream {
+            stream: ChatCompletionIter {
+                inner: stream,
+                runtime: self.runtime.clone(),
+            },
+            sources,
+        })
+    }
+
+    /// Chunk a text file into chunks.
+    ///
+    /// # Arguments
+    ///
+    /// * `file_path` - The path to the file to chunk. Note that the file should be a `txt` or `md` file.
+    ///
+    /// * `chunk_capacity` - The capacity of each chunk.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the chunks or an error.
+    #[cfg(feature = "rag")]
+    pub fn rag_chunk_file(
+        &self,
+        file_path: impl AsRef<Path>,
+        chunk_capacity: usize,
+    ) -> Result<ChunksResponse, LlamaEdgeError> {
+        self.block_on(self.inner.rag_chunk_file(file_path, chunk_capacity))
+    }
+
+    /// Ingest documents into a VectorDB collection.
+    ///
+    /// # Arguments
+    ///
+    /// * `paths` - The files and directories to ingest. Directories are searched for files with one of the extensions set in `options`.
+    ///
+    /// * `params` - The parameters for the embeddings. `vdb_server_url` and `vdb_collection_name` must be set.
+    ///
+    /// * `options` - The options for the ingestion.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the number of chunks indexed together with the ingested and failed files, or an error if the arguments are invalid or a directory cannot be read.
+    #[cfg(feature = "rag")]
+    pub fn ingest(
+        &self,
+        paths: impl IntoIterator<Item = impl AsRef<Path>>,
+        params: EmbeddingsParams,
+        options: IngestOptions,
+    ) -> Result<IngestSummary, LlamaEdgeError> {
+        self.block_on(self.inner.ingest(paths, params, options))
+    }
+}
+impl fmt::Debug for Client {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Client")
+            .field("inner", &self.inner)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Blocking iterator over the chunks of a streamed chat completion, returned by [`Client::chat_stream`].
+pub struct ChatCompletionIter {
+    inner: ChatCompletionStream,
+    runtime: Arc<Runtime>,
+}
+impl Iterator for ChatCompletionIter {
+    type Item = Result<ChatCompletionChunk, LlamaEdgeError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.runtime.block_on(self.inner.next())
+    }
+}
+impl fmt::Debug for ChatCompletionIter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ChatCompletionIter").finish_non_exhaustive()
+    }
+}
@@ -0,0 +1,425 @@
+//! Stateful conversations that keep track of the chat history.
+
+use crate::{
+    error::LlamaEdgeError,
+    params::ChatParams,
+    stream::ChatCompletionStream,
+    tools::{ToolRegistry, ToolRun},
+    Client,
+};
+use endpoints::chat::{
+    ChatCompletionAssistantMessage, ChatCompletionChunk, ChatCompletionObject,
+    ChatCompletionRequestMessage, ChatCompletionSystemMessage, ChatCompletionUserMessage,
+    ChatCompletionUserMessageContent, Function, ToolCall,
+};
+use futures::{Stream, StreamExt};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::{
+    collections::BTreeMap,
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+/// Strategy for trimming the chat history of a [`Conversation`].
+///
+/// A turn starts with a user message and includes all the assistant and tool messages that follow it. The system prompt and the latest turn are always kept.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case", tag = "type", content = "value")]
+pub enum HistoryTrim {
+    /// Keep the whole history.
+    #[default]
+    None,
+    /// Keep the last `N` turns.
+    LastTurns(usize),
+    /// Drop the oldest turns until the text content of the system prompt and the history fits into the given number of characters.
+    MaxChars(usize),
+}
+
+/// A conversation with the model, owning the system prompt and the chat history.
+///
+/// The user message and the response of the model are appended to the history after each successful request. The conversation can be serialized to JSON to persist a session.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Conversation {
+    system_prompt: Option<String>,
+    messages: Vec<ChatCompletionRequestMessage>,
+    #[serde(default)]
+    history_trim: HistoryTrim,
+}
+impl Conversation {
+    /// Create an empty conversation without a system prompt.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the system prompt sent at the beginning of every request.
+    pub fn with_system_prompt(mut self, system_prompt: impl Into<String>) -> Self {
+        self.system_prompt = Some(system_prompt.into());
+        self.trim();
+        self
+    }
+
+    /// Set the strategy for trimming the chat history. Defaults to `HistoryTrim::None`.
+    pub fn with_history_trim(mut self, history_trim: HistoryTrim) -> Self {
+        self.history_trim = history_trim;
+        self.trim();
+        self
+    }
+
+    /// Get the system prompt.
+    pub fn system_prompt(&self) -> Option<&str> {
+        self.system_prompt.as_deref()
+    }
+
+    /// Get the chat history, without the system prompt.
+    pub fn history(&self) -> &[ChatCompletionRequestMessage] {
+        &self.messages
+    }
+
+    /// Clear the chat history. The system prompt is kept.
+    pub fn clear(&mut self) {
+        self.messages.clear();
+    }
+
+    /// Append a message to the chat history and trim the history.
+    pub fn push(&mut self, message: ChatCompletionRequestMessage) {
+        self.messages.push(message);
+        self.trim();
+    }
+
+    /// Get the messages sent in the next request, i.e. the system prompt followed by the chat history.
+    pub fn messages(&self) -> Vec<ChatCompletionRequestMessage> {
+        let mut messages = Vec::with_capacity(self.messages.len() + 1);
+        if let Some(system_prompt) = &self.system_prompt {
+            messages.push(ChatCompletionRequestMessage::System(
+                ChatCompletionSystemMessage::new(system_prompt.as_str(), None),
+            ));
+        }
+        messages.extend(self.messages.iter().cloned());
+        messages
+    }
+
+    /// Send a user message and append the answer of the model to the chat history.
+    ///
+    /// # Arguments
+    ///
+    /// * `client` - The client to send the request with.
+    ///
+    /// * `user_message` - The user message.
+    ///
+    /// * `params` - The parameters for the chat completion.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the chat completion or an error. On error, the chat history is left unchanged.
+    pub async fn chat(
+        &mut self,
+        client: &Client,
+        user_message: impl Into<String>,
+        params: &ChatParams,
+    ) -> Result<ChatCompletionObject, LlamaEdgeError> {
+        let snapshot = self.messages.clone();
+        self.push(user_message_from(user_message));
+
+        match client.chat_completion(&self.messages(), params).await {
+            Ok(chat_completion) => {
+                if let Some(choice) = chat_completion.choices.first() {
+                    let tool_calls = if choice.message.tool_calls.is_empty() {
+                        None
+                    } else {
+                        Some(choice.message.tool_calls.clone())
+                    };
+                    self.push(ChatCompletionRequestMessage::Assistant(
+                        ChatCompletionAssistantMessage::new(
+                            choice.message.content.clone(),
+                            None,
+                            tool_calls,
+                        ),
+                    ));
+                }
+
+                Ok(chat_completion)
+            }
+            Err(e) => {
+                self.messages = snapshot;
+                Err(e)
+            }
+        }
+    }
+
+    /// Send a user message with streaming. The answer of the model is appended to the chat history once the stream has been consumed to the end.
+    ///
+    /// # Arguments
+    ///
+    /// * `client` - The client to send the request with.
+    ///
+    /// * `user_message` - The user message.
+    ///
+    /// * `params` - The parameters for the chat completion.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the stream of chat completion chunks or an error. If the request or the stream fails, or the stream is dropped before its end, the chat history is left unchanged.
+    pub async fn chat_stream(
+        &mut self,
+        client: &Client,
+        user_message: impl Into<String>,
+        params: &ChatParams,
+    ) -> Result<ConversationStream<'_>, LlamaEdgeError> {
+        let snapshot = self.messages.clone();
+        self.push(user_message_from(user_message));
+
+        match client.chat_stream(&self.messages(), params).await {
+            Ok(inner) => Ok(ConversationStream {
+                conversation: self,
+                snapshot,
+                inner,
+                content: String::new(),
+                tool_calls: BTreeMap::new(),
+                finished: false,
+            }),
+            Err(e) => {
+                self.messages = snapshot;
+                Err(e)
+            }
+        }
+    }
+
+    /// Send a user message and execute the tool calls of the model with [`Client::run_with_tools`]. The tool calls, the tool results and the final answer are appended to the chat history.
+    ///
+    /// # Arguments
+    ///
+    /// * `client` - The client to send the requests with.
+    ///
+    /// * `user_message` - The user message.
+    ///
+    /// * `params` - The parameters for the chat completion.
+    ///
+    /// * `registry` - The registry of the functions the model may call.
+    ///
+    /// * `max_iterations` - The maximum number of chat completion requests to send.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the outcome of the run or an error. On error, the chat history is left unchanged.
+    pub async fn run_with_tools(
+        &mut self,
+        client: &Client,
+        user_message: impl Into<String>,
+        params: &ChatParams,
+        registry: &ToolRegistry,
+        max_iterations: usize,
+    ) -> Result<ToolRun, LlamaEdgeError> {
+        let snapshot = self.messages.clone();
+        self.push(user_message_from(user_message));
+
+        let messages = self.messages();
+        match client
+            .run_with_tools(&messages, params, registry, max_iterations)
+            .await
+        {
+            Ok(run) => {
+                for message in run.messages.iter().skip(messages.len()) {
+                    self.messages.push(message.clone());
+                }
+                self.trim();
+
+                Ok(run)
+            }
+            Err(e) => {
+                self.messages = snapshot;
+                Err(e)
+            }
+        }
+    }
+
+    /// Serialize the conversation to JSON.
+    pub fn to_json(&self) -> Result<String, LlamaEdgeError> {
+        serde_json::to_string(self).map_err(|e| {
+            LlamaEdgeError::Operation(format!("Failed to serialize the conversation: {}", e))
+        })
+    }
+
+    /// Deserialize a conversation from JSON.
+    pub fn from_json(json: impl AsRef<str>) -> Result<Self, LlamaEdgeError> {
+        serde_json::from_str(json.as_ref()).map_err(|e| LlamaEdgeError::Decode {
+            message: format!("Failed to deserialize the conversation: {}", e),
+            body: json.as_ref().to_string(),
+        })
+    }
+
+    /// Drop the oldest turns according to the trimming strategy.
+    fn trim(&mut self) {
+        let turn_starts = self
+            .messages
+            .iter()
+            .enumerate()
+            .filter(|(_, message)| matches!(message, ChatCompletionRequestMessage::User(_)))
+            .map(|(i, _)| i)
+            .collect::<Vec<_>>();
+        if turn_starts.len() <= 1 {
+            return;
+        }
+
+        let start = match self.history_trim {
+            HistoryTrim::None => 0,
+            HistoryTrim::LastTurns(n) => {
+                let n = n.max(1);
+                if turn_starts.len() > n {
+                    turn_starts[turn_starts.len() - n]
+                } else {
+                    0
+                }
+            }
+            HistoryTrim::MaxChars(max_chars) => {
+                let system_prompt_chars = self
+                    .system_prompt
+                    .as_ref()
+                    .map_or(0, |system_prompt| system_prompt.chars().count());
+                let mut total: usize =
+                    system_prompt_chars + self.messages.iter().map(message_chars).sum::<usize>();
+                let mut start = 0;
+                // drop whole turns, up to the start of the latest one
+                for &next_turn in turn_starts.iter().skip(1) {
+                    if total <= max_chars {
+                        break;
+                    }
+                    total -= self.messages[start..next_turn]
+                        .iter()
+                        .map(message_chars)
+                        .sum::<usize>();
+                    start = next_turn;
+                }
+                start
+            }
+        };
+
+        if start > 0 {
+            self.messages.drain(..start);
+        }
+    }
+}
+
+/// Stream of a chat completion sent by [`Conversation::chat_stream`].
+///
+/// The content and the tool calls of the streamed chunks are collected, and appended to the chat history as an assistant message when the stream ends. If the stream is dropped before its end, the user message is removed from the chat history.
+pub struct ConversationStream<'a> {
+    conversation: &'a mut Conversation,
+    snapshot: Vec<ChatCompletionRequestMessage>,
+    inner: ChatCompletionStream,
+    content: String,
+    /// The tool calls streamed so far, by index. Their arguments arrive in pieces.
+    tool_calls: BTreeMap<u64, ToolCall>,
+    finished: bool,
+}
+impl Stream for ConversationStream<'_> {
+    type Item = Result<ChatCompletionChunk, LlamaEdgeError>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        if self.finished {
+            return Poll::Ready(None);
+        }
+
+        match self.inner.poll_next_unpin(cx) {
+            Poll::Ready(Some(Ok(chunk))) => {
+                if let Some(choice) = chunk.choices.first() {
+                    if let Some(content) = choice.delta.content.as_ref() {
+                        self.content.push_str(content);
+                    }
+                    for delta in choice.delta.tool_calls.iter() {
+                        let tool_call = self
+                            .tool_calls
+                            .entry(u64::from(delta.index))
+                            .or_insert_with(|| ToolCall {
+                                id: String::new(),
+                                ty: "function".to_string(),
+                                function: Function {
+                                    name: String::new(),
+                                    arguments: String::new(),
+                                },
+                            });
+                        // the id, type and name are sent once, and the arguments in pieces
+                        if !delta.id.is_empty() {
+                            tool_call.id = delta.id.clone();
+                        }
+                        if !delta.ty.is_empty() {
+                            tool_call.ty = delta.ty.clone();
+                        }
+                        if !delta.function.name.is_empty() {
+                            tool_call.function.name = delta.function.name.clone();
+                        }
+                        tool_call
+                            .function
+                            .arguments
+                            .push_str(&delta.function.arguments);
+                    }
+                }
+                Poll::Ready(Some(Ok(chunk)))
+            }
+            Poll::Ready(Some(Err(e))) => {
+                self.finished = true;
+                let snapshot = std::mem::take(&mut self.snapshot);
+                self.conversation.messages = snapshot;
+                Poll::Ready(Some(Err(e)))
+            }
+            Poll::Ready(None) => {
+                self.finished = true;
+                let content = std::mem::take(&mut self.content);
+                let tool_calls = std::mem::take(&mut self.tool_calls)
+                    .into_values()
+                    .collect::<Vec<_>>();
+                let message = if tool_calls.is_empty() {
+                    ChatCompletionAssistantMessage::new(Some(content), None, None)
+                } else {
+                    let content = (!content.is_empty()).then_some(content);
+                    ChatCompletionAssistantMessage::new(content, None, Some(tool_calls))
+                };
+                self.conversation
+                    .push(ChatCompletionRequestMessage::Assistant(message));
+                Poll::Ready(None)
+            }
+            Poll::Pending => Poll::Pending,
+        }
+    }
+}
+impl Drop for ConversationStream<'_> {
+    fn drop(&mut self) {
+        // an interrupted answer is not recorded, so the next turn does not follow a user message without a reply
+        if !self.finished {
+            self.conversation.messages = std::mem::take(&mut self.snapshot);
+        }
+    }
+}
+
+/// Create a user message with text content.
+fn user_message_from(text: impl Into<String>) -> ChatCompletionRequestMessage {
+    ChatCompletionRequestMessage::User(ChatCompletionUserMessage::new(
+        ChatCompletionUserMessageContent::Text(text.into()),
+        None,
+    ))
+}
+
+/// Count the characters of the text content of a message, including the arguments of tool calls.
+fn message_chars(message: &ChatCompletionRequestMessage) -> usize {
+    fn text_chars(value: &Value) -> usize {
+        match value {
+            Value::String(text) => text.chars().count(),
+            Value::Array(parts) => parts.iter().map(|part| text_chars(&part["text"])).sum(),
+            _ => 0,
+        }
+    }
+
+    let value = match serde_json::to_value(message) {
+        Ok(value) => value,
+        Err(_) => return 0,
+    };
+    let tool_call_chars = match value["tool_calls"].as_array() {
+        Some(tool_calls) => tool_calls
+            .iter()
+            .map(|tool_call| text_chars(&tool_call["function"]["arguments"]))
+            .sum(),
+        None => 0,
+    };
+
+    text_chars(&value["content"]) + tool_call_chars
+}
@@ -0,0 +1,59 @@
+//! The endpoints of the LlamaEdge API: their paths, and whether requests to them can be sent again safely.
+
+/// Endpoints of the LlamaEdge API.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Endpoint {
+    /// `/v1/chat/completions`
+    ChatCompletions,
+    /// `/v1/embeddings`
+    Embeddings,
+    /// `/v1/models`
+    Models,
+    /// `/v1/files`
+    Files,
+    /// `/v1/audio/transcriptions`
+    AudioTranscriptions,
+    /// `/v1/audio/translations`
+    AudioTranslations,
+    /// `/v1/images/generations`
+    ImageGenerations,
+    /// `/v1/images/edits`
+    ImageEdits,
+    /// `/v1/images/variations`
+    ImageVariations,
+    /// `/v1/retrieve`
+    Retrieve,
+    /// `/v1/chunks`
+    Chunks,
+}
+impl Endpoint {
+    /// Get the path of the endpoint, relative to the API version segment, e.g. `chat/completions`.
+    pub fn path(&self) -> &'static str {
+        match self {
+            Endpoint::ChatCompletions => "chat/completions",
+            Endpoint::Embeddings => "embeddings",
+            Endpoint::Models => "models",
+            Endpoint::Files => "files",
+            Endpoint::AudioTranscriptions => "audio/transcriptions",
+            Endpoint::AudioTranslations => "audio/translations",
+            Endpoint::ImageGenerations => "images/generations",
+            Endpoint::ImageEdits => "images/edits",
+            Endpoint::ImageVariations => "images/variations",
+            Endpoint::Retrieve => "retrieve",
+            Endpoint::Chunks => "chunks",
+        }
+    }
+
+    /// Check if a request to the endpoint can be sent again without creating duplicate resources on the server.
+    ///
+    /// The retry policy of the client applies to all the requests to these endpoints. Requests to the other endpoints are retried only if they use an idempotent method, such as reading or deleting a file, or if a policy is set for the endpoint explicitly.
+    pub fn is_idempotent(&self) -> bool {
+        !matches!(
+            self,
+            Endpoint::Files
+                | Endpoint::ImageGenerations
+                | Endpoint::ImageEdits
+                | Endpoint::ImageVariations
+        )
+    }
+}
@@ -1,5 +1,7 @@
 //! Error types.
 
+use crate::retry::RetryPolicy;
+use reqwest::StatusCode;
 use thiserror::Error;
 
 /// Error types for the Llama Core library.
@@ -14,4 +16,111 @@ pub enum LlamaEdgeError {
     /// Errors in invalid argument.
     #[error("Invalid argument: {0}")]
     InvalidArgument(String),
+    /// Errors returned by the server with a non-success status code.
+    #[error("{endpoint} returned {status}: {message}")]
+    Http {
+        /// The HTTP status code of the response.
+        status: StatusCode,
+        /// The error message from the response body.
+        message: String,
+        /// The path of the endpoint that returned the error.
+        endpoint: String,
+    },
+    /// Errors in sending the request or receiving the response, such as a refused connection.
+    #[error("Transport error: {0}")]
+    Transport(#[source] reqwest::Error),
+    /// Errors in decoding the response body.
+    #[error("Failed to decode the response: {message}")]
+    Decode {
+        /// The reason the body could not be decoded.
+        message: String,
+        /// The raw response body.
+        body: String,
+    },
+    /// Errors in requests that timed out.
+    #[error("Request timed out: {0}")]
+    Timeout(#[source] reqwest::Error),
+}
+impl LlamaEdgeError {
+    /// Get the HTTP status code, if the error was returned by the server.
+    pub fn status(&self) -> Option<StatusCode> {
+        match self {
+            LlamaEdgeError::Http { status, .. } => Some(*status),
+            _ => None,
+        }
+    }
+
+    /// Check if the failed request may succeed when sent again, i.e. if it would be retried by the default [`RetryPolicy`].
+    ///
+    /// Timeouts, failures to connect, connections reset or closed by the server, and the status codes `408`, `429`, `502`, `503` and `504` are considered retryable. Other transport errors, such as a failing request body, would fail the same way on every attempt.
+    pub fn is_retryable(&self) -> bool {
+        RetryPolicy::new().should_retry(self)
+    }
+}
+impl From<reqwest::Error> for LlamaEdgeError {
+    fn from(e: reqwest::Error) -> Self {
+        if e.is_timeout() {
+            LlamaEdgeError::Timeout(e)
+        } else if e.is_decode() {
+            LlamaEdgeError::Decode {
+                message: e.to_string(),
+                body: String::new(),
+            }
+        } else {
+            LlamaEdgeError::Transport(e)
+        }
+    }
+}
+
+/// Check if a transport error is a failure to connect, or a connection reset or closed before the response was received.
+pub(crate) fn is_connection_error(e: &reqwest::Error) -> bool {
+    if e.is_connect() {
+        return true;
+    }
+
+    let mut source = std::error::Error::source(e);
+    while let Some(error) = source {
+        if let Some(error) = error.downcast_ref::<hyper::Error>() {
+            if error.is_incomplete_message() || error.is_closed() {
+                return true;
+            }
+        }
+        if let Some(error) = error.downcast_ref::<std::io::Error>() {
+            if matches!(
+                error.kind(),
+                std::io::ErrorKind::ConnectionReset
+                    | std::io::ErrorKind::ConnectionAborted
+                    | std::io::ErrorKind::BrokenPipe
+                    | std::io::ErrorKind::UnexpectedEof
+            ) {
+                return true;
+            }
+        }
+        source = error.source();
+    }
+
+    false
+}
+
+/// Extract the error message from the body of an error response.
+///
+/// The LlamaEdge API server reports errors either as plain text or as a JSON object in the OpenAI format, i.e. `{"error": {"message": "..."}}`. VectorDB servers report them as `{"status": {"error": "..."}}`.
+pub(crate) fn error_message_from_body(body: &str) -> String {
+    if let Ok(value) = serde_json::from_str::<serde_json::Value>(body) {
+        let message = value
+            .pointer("/error/message")
+            .and_then(serde_json::Value::as_str)
+            .or_else(|| value.get("error").and_then(serde_json::Value::as_str))
+            .or_else(|| value.get("message").and_then(serde_json::Value::as_str))
+            .or_else(|| {
+                value
+                    .pointer("/status/error")
+                    .and_then(serde_json::Value::as_str)
+            });
+        if let Some(message) = message {
+            return message.to_string();
+        }
+    }
+
+    body.trim().to_string()
 }
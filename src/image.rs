@@ -0,0 +1,299 @@
+//! Types for the images returned by the image generation and editing APIs.
+
+use crate::{error::LlamaEdgeError, upload::UploadFile};
+use base64::{engine::general_purpose::STANDARD, Engine};
+use bytes::Bytes;
+use endpoints::{files::FileObject, images::ImageObject};
+use std::{
+    fmt,
+    path::{Path, PathBuf},
+};
+
+/// The format of an image, detected from its content.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ImageFormat {
+    /// Portable Network Graphics.
+    Png,
+    /// JPEG.
+    Jpeg,
+    /// WebP.
+    Webp,
+}
+impl ImageFormat {
+    /// Detect the format of an image from the signature at the start of its content.
+    ///
+    /// # Arguments
+    ///
+    /// * `bytes` - The content of the image, or its first bytes.
+    ///
+    /// # Returns
+    ///
+    /// The format of the image, or `None` if it is neither PNG, JPEG nor WebP.
+    pub fn detect(bytes: &[u8]) -> Option<Self> {
+        match bytes {
+            [0x89, b'P', b'N', b'G', 0x0D, 0x0A, 0x1A, 0x0A, ..] => Some(ImageFormat::Png),
+            [0xFF, 0xD8, 0xFF, ..] => Some(ImageFormat::Jpeg),
+            [b'R', b'I', b'F', b'F', _, _, _, _, b'W', b'E', b'B', b'P', ..] => {
+                Some(ImageFormat::Webp)
+            }
+            _ => None,
+        }
+    }
+
+    /// Get the content type of the format, e.g. `image/png`.
+    pub fn mime_type(&self) -> &'static str {
+        match self {
+            ImageFormat::Png => "image/png",
+            ImageFormat::Jpeg => "image/jpeg",
+            ImageFormat::Webp => "image/webp",
+        }
+    }
+
+    /// Get the usual file extension of the format, without the leading dot.
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ImageFormat::Png => "png",
+            ImageFormat::Jpeg => "jpg",
+            ImageFormat::Webp => "webp",
+        }
+    }
+}
+impl fmt::Display for ImageFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.extension())
+    }
+}
+
+/// The raw content of a generated image, as returned by [`Client::image_bytes`](crate::Client::image_bytes).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ImageData {
+    bytes: Bytes,
+    format: Option<ImageFormat>,
+}
+impl ImageData {
+    /// Create an image from its raw content. The format is detected from the content.
+    pub fn new(bytes: impl Into<Bytes>) -> Self {
+        let bytes = bytes.into();
+        let format = ImageFormat::detect(&bytes);
+        Self { bytes, format }
+    }
+
+    /// Get the raw content of the image.
+    pub fn bytes(&self) -> &Bytes {
+        &self.bytes
+    }
+
+    /// Get the raw content of the image, consuming it.
+    pub fn into_bytes(self) -> Bytes {
+        self.bytes
+    }
+
+    /// Get the format of the image, if it is PNG, JPEG or WebP.
+    pub fn format(&self) -> Option<ImageFormat> {
+        self.format
+    }
+}
+
+/// Where the content of an [`ImageObject`] is found.
+pub(crate) enum ImageSource<'a> {
+    /// The content is embedded in the response.
+    Embedded(Bytes),
+    /// The content is to be downloaded from a URL, which may be relative to the server base URL.
+    Url(&'a str),
+}
+impl<'a> ImageSource<'a> {
+    /// Get the source of the content of an image. The `b64_json` field is preferred over the `url` field, and `data:` URLs are decoded in place.
+    pub(crate) fn of(image: &'a ImageObject) -> Result<Self, LlamaEdgeError> {
+        if let Some(b64_json) = image.b64_json.as_deref().filter(|b64| !b64.is_empty()) {
+            return decode_base64(b64_json).map(ImageSource::Embedded);
+        }
+
+        let url = image
+            .url
+            .as_deref()
+            .filter(|url| !url.is_empty())
+            .ok_or_else(|| {
+                LlamaEdgeError::InvalidArgument(
+                    "The image has neither `b64_json` nor `url`".to_string(),
+                )
+            })?;
+        if !url.starts_with("data:") {
+            return Ok(ImageSource::Url(url));
+        }
+
+        match url.split_once(";base64,") {
+            Some((_, b64)) => decode_base64(b64).map(ImageSource::Embedded),
+            None => Err(LlamaEdgeError::Decode {
+                message: "The data URL of the image is not base64-encoded".to_string(),
+                body: url.to_string(),
+            }),
+        }
+    }
+}
+
+/// Decode base64-encoded image content. Whitespace, such as line breaks, is ignored.
+fn decode_base64(b64: &str) -> Result<Bytes, LlamaEdgeError> {
+    let b64 = b64
+        .chars()
+        .filter(|c| !c.is_ascii_whitespace())
+        .collect::<String>();
+
+    STANDARD
+        .decode(&b64)
+        .map(Bytes::from)
+        .map_err(|e| LlamaEdgeError::Decode {
+            message: format!("Failed to decode the base64 image: {}", e),
+            body: b64,
+        })
+}
+
+/// An image sent to the server: a local file, content held in memory, a file to send such as one read from an async reader, or a file previously uploaded with [`Client::upload_file`](crate::Client::upload_file).
+#[derive(Debug, Clone)]
+pub enum ImageInput {
+    /// The path to a local image file.
+    Path(PathBuf),
+    /// The content of an image held in memory.
+    Bytes {
+        /// The content of the image.
+        bytes: Bytes,
+        /// The name of the file sent to the server.
+        filename: String,
+    },
+    /// A file to send, read from a path, from memory or from an async reader. Its content type is kept if it is set explicitly.
+    Upload(UploadFile),
+    /// An image previously uploaded to the server.
+    File(FileObject),
+}
+impl ImageInput {
+    /// Create an image input from the given path.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The path to the image file.
+    pub fn from_path(path: impl AsRef<Path>) -> Self {
+        ImageInput::Path(path.as_ref().to_path_buf())
+    }
+
+    /// Create an image input from content held in memory.
+    ///
+    /// # Arguments
+    ///
+    /// * `bytes` - The content of the image.
+    ///
+    /// * `filename` - The name of the file sent to the server.
+    pub fn from_bytes(bytes: impl Into<Bytes>, filename: impl Into<String>) -> Self {
+        ImageInput::Bytes {
+            bytes: bytes.into(),
+            filename: filename.into(),
+        }
+    }
+}
+impl From<&Path> for ImageInput {
+    fn from(path: &Path) -> Self {
+        Self::from_path(path)
+    }
+}
+impl From<PathBuf> for ImageInput {
+    fn from(path: PathBuf) -> Self {
+        ImageInput::Path(path)
+    }
+}
+impl From<&PathBuf> for ImageInput {
+    fn from(path: &PathBuf) -> Self {
+        Self::from_path(path)
+    }
+}
+impl From<&str> for ImageInput {
+    fn from(path: &str) -> Self {
+        Self::from_path(path)
+    }
+}
+impl From<String> for ImageInput {
+    fn from(path: String) -> Self {
+        ImageInput::Path(PathBuf::from(path))
+    }
+}
+impl From<&String> for ImageInput {
+    fn from(path: &String) -> Self {
+        Self::from_path(path)
+    }
+}
+impl From<UploadFile> for ImageInput {
+    fn from(file: UploadFile) -> Self {
+        ImageInput::Upload(file)
+    }
+}
+impl From<FileObject> for ImageInput {
+    fn from(file: FileObject) -> Self {
+        ImageInput::File(file)
+    }
+}
+
+/// Read the width and the height of an image from its header.
+///
+/// # Arguments
+///
+/// * `bytes` - The content of the image.
+///
+/// # Returns
+///
+/// The width and the height of the image in pixels, or `None` if it is neither PNG, JPEG nor WebP, or its header is truncated.
+pub fn image_dimensions(bytes: &[u8]) -> Option<(u32, u32)> {
+    let read = |offset: usize, len: usize| bytes.get(offset..offset + len);
+    let be_u16 = |offset| read(offset, 2).map(|b| u16::from_be_bytes([b[0], b[1]]) as u32);
+    let be_u32 = |offset| read(offset, 4).map(|b| u32::from_be_bytes([b[0], b[1], b[2], b[3]]));
+    let le_u16 = |offset| read(offset, 2).map(|b| u16::from_le_bytes([b[0], b[1]]) as u32);
+    let le_u24 = |offset| read(offset, 3).map(|b| u32::from_le_bytes([b[0], b[1], b[2], 0]));
+    let le_u32 = |offset| read(offset, 4).map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]]));
+
+    match ImageFormat::detect(bytes)? {
+        // the IHDR chunk is always first
+        ImageFormat::Png => Some((be_u32(16)?, be_u32(20)?)),
+        ImageFormat::Jpeg => {
+            // walk the segments up to the start-of-frame marker
+            let mut offset = 2;
+            loop {
+                if *bytes.get(offset)? != 0xFF {
+                    return None;
+                }
+                let marker = *bytes.get(offset + 1)?;
+                match marker {
+                    // padding before a marker
+                    0xFF => offset += 1,
+                    // markers without a length
+                    0x01 | 0xD0..=0xD7 => offset += 2,
+                    // start-of-frame markers, excluding DHT, JPG and DAC
+                    0xC0..=0xCF if !matches!(marker, 0xC4 | 0xC8 | 0xCC) => {
+                        return Some((be_u16(offset + 7)?, be_u16(offset + 5)?));
+                    }
+                    _ => offset += 2 + be_u16(offset + 2)? as usize,
+                }
+            }
+        }
+        ImageFormat::Webp => match bytes.get(12..16)? {
+            // the dimensions follow the frame tag and the start code of the key frame
+            b"VP8 " => Some((le_u16(26)? & 0x3FFF, le_u16(28)? & 0x3FFF)),
+            // the dimensions minus one are packed in 14 bits each after the signature byte
+            b"VP8L" => {
+                let bits = le_u32(21)?;
+                Some(((bits & 0x3FFF) + 1, ((bits >> 14) & 0x3FFF) + 1))
+            }
+            // the canvas dimensions minus one follow the flags
+            b"VP8X" => Some((le_u24(24)? + 1, le_u24(27)? + 1)),
+            _ => None,
+        },
+    }
+}
+
+/// Check that a mask has the same dimensions as the image it applies to. Images whose dimensions cannot be read are not checked.
+pub(crate) fn validate_mask(image: &[u8], mask: &[u8]) -> Result<(), LlamaEdgeError> {
+    match (image_dimensions(image), image_dimensions(mask)) {
+        (Some(image), Some(mask)) if image != mask => {
+            Err(LlamaEdgeError::InvalidArgument(format!(
+                "The mask is {}x{} pixels, but the image is {}x{} pixels",
+                mask.0, mask.1, image.0, image.1
+            )))
+        }
+        _ => Ok(()),
+    }
+}
@@ -52,12 +52,40 @@
 //! ```
 //!
 //! **Note:** To run the example, LlamaEdge API server should be deployed and running on your local machine. Refer to [Quick Start](https://github.com/LlamaEdge/LlamaEdge?tab=readme-ov-file#quick-start) for more details on how to deploy and run the server.
+//!
+//! ## Blocking client
+//!
+//! The synchronous client is available behind the `blocking` feature as [`blocking::Client`](crate::blocking::Client). It mirrors the methods of the async client, and returns streamed chat completions as an `Iterator`.
 
 #![cfg_attr(docsrs, feature(doc_cfg, doc_auto_cfg))]
 
+#[cfg(feature = "audio")]
+pub mod audio;
+pub mod auth;
+#[cfg(feature = "blocking")]
+pub mod blocking;
+pub mod conversation;
+pub mod endpoint;
 pub mod error;
+#[cfg(feature = "image")]
+pub mod image;
 pub mod params;
+#[cfg(feature = "rag")]
+pub mod rag;
+pub mod retry;
+pub mod stream;
+pub mod tools;
+pub mod upload;
 
+#[cfg(feature = "audio")]
+use audio::{
+    long::{self, LongTranscriptionOptions},
+    wav::Wav,
+    Transcript, TranscriptionFormat, VerboseTranscript,
+};
+use auth::{ApiKey, AuthProvider};
+use bytes::Bytes;
+use endpoint::Endpoint;
 #[cfg(feature = "audio")]
 use endpoints::audio::{transcription::TranscriptionObject, translation::TranslationObject};
 #[cfg(feature = "image")]
@@ -70,14 +98,19 @@ use endpoints::{
 };
 use endpoints::{
     chat::{
-        ChatCompletionObject, ChatCompletionRequest, ChatCompletionRequestMessage, StreamOptions,
+        ChatCompletionAssistantMessage, ChatCompletionObject, ChatCompletionRequest,
+        ChatCompletionRequestMessage, ChatCompletionToolMessage, StreamOptions,
     },
     embeddings::{EmbeddingRequest, EmbeddingsResponse, InputText},
-    files::FileObject,
+    files::{DeleteFileStatus, FileObject, ListFilesResponse},
     models::{ListModelsResponse, Model},
 };
 use error::LlamaEdgeError;
-use futures::{stream::TryStream, StreamExt};
+use futures::StreamExt;
+#[cfg(feature = "audio")]
+use futures::TryStreamExt;
+#[cfg(feature = "image")]
+use image::{ImageData, ImageInput, ImageSource};
 #[cfg(feature = "rag")]
 use params::RagChatParams;
 use params::{ChatParams, EmbeddingsParams};
@@ -85,16 +118,44 @@ use params::{ChatParams, EmbeddingsParams};
 use params::{ImageCreateParams, ImageEditParams};
 #[cfg(feature = "audio")]
 use params::{TranscriptionParams, TranslationParams};
-use reqwest::multipart;
-use std::path::Path;
+#[cfg(feature = "rag")]
+use rag::{
+    ingest::{IngestEvent, IngestFailure, IngestOptions, IngestSummary, IngestedFile},
+    RagChatCompletion, RagChatStream,
+};
+use reqwest::{
+    header::{HeaderMap, HeaderName, HeaderValue},
+    multipart, Method,
+};
+use retry::RetryPolicy;
+use serde::de::DeserializeOwned;
+use std::{collections::HashMap, fmt, path::Path, sync::Arc, time::Duration};
+use stream::ChatCompletionStream;
+use tokio::io::AsyncWriteExt;
+use tools::{ToolRegistry, ToolRun};
+use upload::UploadFile;
 use url::Url;
 
+/// The API version segment used when none is configured.
+const DEFAULT_API_VERSION: &str = "v1";
+
+/// The `User-Agent` header sent when none is configured.
+const DEFAULT_USER_AGENT: &str = concat!("llamaedge-rust/", env!("CARGO_PKG_VERSION"));
+
 /// Client for the LlamaEdge API.
+///
+/// The client holds a single `reqwest::Client`, so the underlying connection pool is shared by all requests. Cloning the client is cheap and shares the same pool.
+#[derive(Clone)]
 pub struct Client {
     server_base_url: Url,
+    api_version: String,
+    http_client: reqwest::Client,
+    auth_provider: Option<Arc<dyn AuthProvider>>,
+    retry_policy: RetryPolicy,
+    endpoint_retry_policies: HashMap<Endpoint, RetryPolicy>,
 }
 impl Client {
-    /// Create a new client.
+    /// Create a new client with the default settings.
     ///
     /// # Arguments
     ///
@@ -104,13 +165,20 @@ impl Client {
     ///
     /// A `Result` containing the client or an error.
     pub fn new(server_base_url: impl AsRef<str>) -> Result<Self, LlamaEdgeError> {
-        let url_str = server_base_url.as_ref().trim_end_matches('/');
-        match Url::parse(url_str) {
-            Ok(url) => Ok(Self {
-                server_base_url: url,
-            }),
-            Err(e) => Err(LlamaEdgeError::UrlParse(e)),
-        }
+        ClientBuilder::new(server_base_url).build()
+    }
+
+    /// Create a builder to configure a new client.
+    ///
+    /// # Arguments
+    ///
+    /// * `server_base_url` - The base URL of the LlamaEdge API server.
+    ///
+    /// # Returns
+    ///
+    /// A `ClientBuilder` instance.
+    pub fn builder(server_base_url: impl AsRef<str>) -> ClientBuilder {
+        ClientBuilder::new(server_base_url)
     }
 
     /// Get the server base URL.
@@ -122,6 +190,137 @@ impl Client {
         &self.server_base_url
     }
 
+    /// Get the API version segment prepended to the path of every endpoint.
+    pub fn api_version(&self) -> &str {
+        &self.api_version
+    }
+
+    /// Get the URL of the given endpoint, preserving the path of the server base URL.
+    ///
+    /// # Arguments
+    ///
+    /// * `endpoint` - The endpoint.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the URL of the endpoint or an error.
+    pub fn endpoint_url(&self, endpoint: Endpoint) -> Result<Url, LlamaEdgeError> {
+        let path = if self.api_version.is_empty() {
+            endpoint.path().to_string()
+        } else {
+            format!("{}/{}", self.api_version, endpoint.path())
+        };
+
+        // the base URL always ends with a slash, so joining a relative path keeps its path prefix
+        Ok(self.server_base_url.join(&path)?)
+    }
+
+    /// Get the URL of an uploaded file, optionally followed by a sub-resource such as `content`.
+    fn file_url(&self, file_id: &str, resource: Option<&str>) -> Result<Url, LlamaEdgeError> {
+        if file_id.trim().is_empty() {
+            return Err(LlamaEdgeError::InvalidArgument(
+                "file_id cannot be empty".to_string(),
+            ));
+        }
+
+        let mut url = self.endpoint_url(Endpoint::Files)?;
+        {
+            let mut segments = url.path_segments_mut().map_err(|_| {
+                LlamaEdgeError::Operation("Failed to build the file URL".to_string())
+            })?;
+            // the file id is percent-encoded, so it cannot escape the files endpoint
+            segments.push(file_id);
+            if let Some(resource) = resource {
+                segments.push(resource);
+            }
+        }
+
+        Ok(url)
+    }
+
+    /// Send a request and check the status code of the response.
+    ///
+    /// A response with a non-success status code is turned into a [`LlamaEdgeError::Http`] error carrying the error message from the server.
+    ///
+    /// If the request fails with a transient error, it is sent again according to the retry policy of the endpoint.
+    async fn send(
+        &self,
+        endpoint: Endpoint,
+        request: reqwest::RequestBuilder,
+    ) -> Result<reqwest::Response, LlamaEdgeError> {
+        let (http_client, request) = request.build_split();
+        let mut request = request?;
+        let retry_policy = self.retry_policy(endpoint, request.method());
+        let max_attempts = retry_policy.map_or(1, |retry_policy| retry_policy.max_attempts());
+
+        let mut attempt = 1;
+        loop {
+            // requests with a streaming body, such as multipart forms, cannot be cloned and are sent only once
+            let next_request = if attempt < max_attempts {
+                request.try_clone()
+            } else {
+                None
+            };
+
+            let builder = reqwest::RequestBuilder::from_parts(http_client.clone(), request);
+            match Self::send_once(builder).await {
+                Ok(response) => return Ok(response),
+                Err(e) => match (retry_policy, next_request) {
+                    (Some(retry_policy), Some(next_request)) if retry_policy.should_retry(&e) => {
+                        tokio::time::sleep(retry_policy.delay(attempt)).await;
+                        request = next_request;
+                        attempt += 1;
+                    }
+                    _ => return Err(e),
+                },
+            }
+        }
+    }
+
+    /// Get the retry policy of a request to the given endpoint, or `None` if the request is not retried.
+    ///
+    /// The retry policy of the client applies to idempotent endpoints, and to requests with an idempotent method such as `GET` and `DELETE` to any endpoint, e.g. reading or deleting files.
+    fn retry_policy(&self, endpoint: Endpoint, method: &Method) -> Option<&RetryPolicy> {
+        match self.endpoint_retry_policies.get(&endpoint) {
+            Some(retry_policy) => Some(retry_policy),
+            None if endpoint.is_idempotent() || method.is_idempotent() => Some(&self.retry_policy),
+            None => None,
+        }
+    }
+
+    /// Send a request once and check the status code of the response.
+    async fn send_once(
+        request: reqwest::RequestBuilder,
+    ) -> Result<reqwest::Response, LlamaEdgeError> {
+        let response = request.send().await?;
+
+        let status = response.status();
+        if status.is_success() {
+            return Ok(response);
+        }
+
+        let endpoint = response.url().path().to_string();
+        let body = response.text().await.unwrap_or_default();
+        Err(LlamaEdgeError::Http {
+            status,
+            message: error::error_message_from_body(&body),
+            endpoint,
+        })
+    }
+
+    /// Create a request to the given URL, carrying the bearer token of the auth provider if any.
+    fn request(&self, method: Method, url: Url) -> reqwest::RequestBuilder {
+        let builder = self.http_client.request(method, url);
+        match self
+            .auth_provider
+            .as_ref()
+            .and_then(|provider| provider.bearer_token())
+        {
+            Some(token) => builder.bearer_auth(token),
+            None => builder,
+        }
+    }
+
     /// Send a chat completion request.
     ///
     /// # Arguments
@@ -132,12 +331,40 @@ impl Client {
     ///
     /// # Returns
     ///
-    /// A `Result` containing the chat completion or an error.
+    /// A `Result` containing the content of the first choice or an error. Use `chat_completion` to get the full response, including tool calls, finish reasons and token usage.
     pub async fn chat(
         &self,
         chat_history: &[ChatCompletionRequestMessage],
         params: &ChatParams,
     ) -> Result<String, LlamaEdgeError> {
+        let chat_completion = self.chat_completion(chat_history, params).await?;
+
+        match chat_completion
+            .choices
+            .first()
+            .and_then(|choice| choice.message.content.as_ref())
+        {
+            Some(content) => Ok(content.clone()),
+            None => Ok("".to_string()),
+        }
+    }
+
+    /// Send a chat completion request and return the full chat completion object.
+    ///
+    /// # Arguments
+    ///
+    /// * `chat_history` - The chat history including the latest user message.
+    ///
+    /// * `params` - The parameters for the chat completion.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the chat completion object or an error.
+    pub async fn chat_completion(
+        &self,
+        chat_history: &[ChatCompletionRequestMessage],
+        params: &ChatParams,
+    ) -> Result<ChatCompletionObject, LlamaEdgeError> {
         if chat_history.is_empty() {
             return Err(LlamaEdgeError::InvalidArgument(
                 "chat_history cannot be empty".to_string(),
@@ -162,23 +389,15 @@ impl Client {
             ..Default::default()
         };
 
-        let url = self.server_base_url.join("/v1/chat/completions")?;
-        let response = reqwest::Client::new()
-            .post(url)
-            .json(&request)
-            .send()
-            .await
-            .map_err(|e| LlamaEdgeError::Operation(e.to_string()))?;
-
-        let response_body = response
-            .json::<ChatCompletionObject>()
-            .await
-            .map_err(|e| LlamaEdgeError::Operation(e.to_string()))?;
+        let url = self.endpoint_url(Endpoint::ChatCompletions)?;
+        let response = self
+            .send(
+                Endpoint::ChatCompletions,
+                self.request(Method::POST, url).json(&request),
+            )
+            .await?;
 
-        match &response_body.choices[0].message.content {
-            Some(content) => Ok(content.clone()),
-            None => Ok("".to_string()),
-        }
+        decode_json::<ChatCompletionObject>(response).await
     }
 
     /// Send a chat completion request with streaming.
@@ -191,15 +410,12 @@ impl Client {
     ///
     /// # Returns
     ///
-    /// A `Result` containing the chat completion stream or an error.
+    /// A `Result` containing the stream of chat completion chunks or an error. The last chunk carries the token usage of the request.
     pub async fn chat_stream(
         &self,
         chat_history: &[ChatCompletionRequestMessage],
         params: &ChatParams,
-    ) -> Result<
-        impl TryStream<Item = Result<String, LlamaEdgeError>, Error = LlamaEdgeError>,
-        LlamaEdgeError,
-    > {
+    ) -> Result<ChatCompletionStream, LlamaEdgeError> {
         if chat_history.is_empty() {
             return Err(LlamaEdgeError::InvalidArgument(
                 "chat_history cannot be empty".to_string(),
@@ -228,105 +444,280 @@ impl Client {
             ..Default::default()
         };
 
-        let url = self.server_base_url.join("/v1/chat/completions")?;
-        let response = reqwest::Client::new()
-            .post(url)
-            .json(&request)
-            .send()
-            .await
-            .map_err(|e| LlamaEdgeError::Operation(e.to_string()))?;
+        let url = self.endpoint_url(Endpoint::ChatCompletions)?;
+        let response = self
+            .send(
+                Endpoint::ChatCompletions,
+                self.request(Method::POST, url).json(&request),
+            )
+            .await?;
 
-        let stream = response.bytes_stream().map(|r| match r {
-            Ok(bytes) => Ok(String::from_utf8_lossy(&bytes).to_string()),
-            Err(e) => Err(LlamaEdgeError::Operation(e.to_string())),
-        });
+        let stream = response
+            .bytes_stream()
+            .map(|r| r.map_err(LlamaEdgeError::from));
 
-        Ok(stream)
+        Ok(ChatCompletionStream::new(stream))
     }
 
-    /// Upload a file to the server.
+    /// Send a chat completion request and execute the tool calls generated by the model until it produces a final answer.
+    ///
+    /// Each tool call is dispatched to the function registered under its name, and the result is appended to the chat history as a tool message before the next request is sent. If a function fails, or the model calls an unknown function, the error message is sent back to the model as the tool result so that it can recover.
     ///
     /// # Arguments
     ///
-    /// * `file` - The file to upload.
+    /// * `chat_history` - The chat history including the latest user message.
+    ///
+    /// * `params` - The parameters for the chat completion. If `params.tools` is `None`, the tools of the registry are used.
+    ///
+    /// * `registry` - The registry of the functions the model may call.
+    ///
+    /// * `max_iterations` - The maximum number of chat completion requests to send.
     ///
     /// # Returns
     ///
-    /// A `Result` containing the file object or an error.
-    pub async fn upload_file(&self, file: impl AsRef<Path>) -> Result<FileObject, LlamaEdgeError> {
-        let abs_file_path = if file.as_ref().is_absolute() {
-            file.as_ref().to_path_buf()
-        } else {
-            std::env::current_dir().unwrap().join(file.as_ref())
-        };
-
-        // check if the file exists
-        if !abs_file_path.exists() {
+    /// A `Result` containing the final chat completion together with the extended chat history, or an error if no final answer was produced within `max_iterations` requests.
+    pub async fn run_with_tools(
+        &self,
+        chat_history: &[ChatCompletionRequestMessage],
+        params: &ChatParams,
+        registry: &ToolRegistry,
+        max_iterations: usize,
+    ) -> Result<ToolRun, LlamaEdgeError> {
+        if max_iterations == 0 {
             return Err(LlamaEdgeError::InvalidArgument(
-                "The file does not exist".to_string(),
+                "max_iterations must be greater than 0".to_string(),
             ));
         }
 
-        // get the filename
-        let filename = abs_file_path
-            .file_name()
-            .unwrap()
-            .to_str()
-            .unwrap()
-            .to_string();
-
-        // get the file extension
-        let file_extension = abs_file_path
-            .extension()
-            .unwrap()
-            .to_str()
-            .unwrap()
-            .to_string();
-
-        let file = tokio::fs::read(abs_file_path)
-            .await
-            .map_err(|e| LlamaEdgeError::Operation(format!("Failed to read audio file: {}", e)))?;
-        let file_part = multipart::Part::bytes(file)
-            .file_name(filename)
-            .mime_str(&format!("audio/{}", file_extension))
-            .map_err(|e| LlamaEdgeError::Operation(e.to_string()))?;
+        let mut params = params.clone();
+        if params.tools.is_none() && !registry.is_empty() {
+            params.tools = Some(registry.tools());
+        }
+
+        let mut messages = chat_history.to_vec();
+        for iteration in 1..=max_iterations {
+            let chat_completion = self.chat_completion(&messages[..], &params).await?;
+
+            let message = match chat_completion.choices.first() {
+                Some(choice) => &choice.message,
+                None => {
+                    return Err(LlamaEdgeError::Operation(
+                        "The chat completion contains no choices".to_string(),
+                    ))
+                }
+            };
+
+            // the model produced the final answer
+            if message.tool_calls.is_empty() {
+                messages.push(ChatCompletionRequestMessage::Assistant(
+                    ChatCompletionAssistantMessage::new(message.content.clone(), None, None),
+                ));
+
+                return Ok(ToolRun {
+                    messages,
+                    chat_completion,
+                    iterations: iteration,
+                });
+            }
+
+            // execute the tool calls
+            messages.push(ChatCompletionRequestMessage::Assistant(
+                ChatCompletionAssistantMessage::new(
+                    message.content.clone(),
+                    None,
+                    Some(message.tool_calls.clone()),
+                ),
+            ));
+            for tool_call in message.tool_calls.iter() {
+                let content = match registry.call(tool_call).await {
+                    Ok(result) => result,
+                    Err(e) => format!("Error: {}", e),
+                };
+
+                messages.push(ChatCompletionRequestMessage::Tool(
+                    ChatCompletionToolMessage::new(content, Some(tool_call.id.clone())),
+                ));
+            }
+        }
+
+        Err(LlamaEdgeError::Operation(format!(
+            "The model did not produce a final answer within {} iterations",
+            max_iterations
+        )))
+    }
 
+    /// Upload a file to the server.
+    ///
+    /// # Arguments
+    ///
+    /// * `file` - The path to the file to upload, or an [`UploadFile`] to send it from memory or an async reader, or to set its content type explicitly. The content type is otherwise detected from the content and the extension of the file.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the file object or an error.
+    pub async fn upload_file(
+        &self,
+        file: impl Into<UploadFile>,
+    ) -> Result<FileObject, LlamaEdgeError> {
+        let file_part = file.into().into_part("file").await?;
         let form = multipart::Form::new().part("file", file_part);
 
-        // upload the audio file
-        let url = self.server_base_url.join("/v1/files")?;
-        let response = reqwest::Client::new()
-            .post(url)
-            .multipart(form)
-            .send()
-            .await
-            .map_err(|e| LlamaEdgeError::Operation(e.to_string()))?;
+        // upload the file
+        let url = self.endpoint_url(Endpoint::Files)?;
+        let response = self
+            .send(
+                Endpoint::Files,
+                self.request(Method::POST, url).multipart(form),
+            )
+            .await?;
 
         // get the file object
-        let file_object = response
-            .json::<FileObject>()
-            .await
-            .map_err(|e| LlamaEdgeError::Operation(e.to_string()))?;
+        let file_object = decode_json::<FileObject>(response).await?;
 
         Ok(file_object)
     }
 
+    /// List the files uploaded to the server.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the list of file objects or an error.
+    pub async fn list_files(&self) -> Result<Vec<FileObject>, LlamaEdgeError> {
+        let url = self.endpoint_url(Endpoint::Files)?;
+        let response = self
+            .send(Endpoint::Files, self.request(Method::GET, url))
+            .await?;
+        let list_files_response = decode_json::<ListFilesResponse>(response).await?;
+
+        Ok(list_files_response.data)
+    }
+
+    /// Retrieve the information of an uploaded file.
+    ///
+    /// # Arguments
+    ///
+    /// * `file_id` - The id of the file.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the file object or an error.
+    pub async fn retrieve_file(
+        &self,
+        file_id: impl AsRef<str>,
+    ) -> Result<FileObject, LlamaEdgeError> {
+        let url = self.file_url(file_id.as_ref(), None)?;
+        let response = self
+            .send(Endpoint::Files, self.request(Method::GET, url))
+            .await?;
+
+        decode_json::<FileObject>(response).await
+    }
+
+    /// Retrieve the content of an uploaded file.
+    ///
+    /// # Arguments
+    ///
+    /// * `file_id` - The id of the file.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the content of the file or an error. Use `download_file` to write large files to disk without holding them in memory.
+    pub async fn retrieve_file_content(
+        &self,
+        file_id: impl AsRef<str>,
+    ) -> Result<Bytes, LlamaEdgeError> {
+        let url = self.file_url(file_id.as_ref(), Some("content"))?;
+        let response = self
+            .send(Endpoint::Files, self.request(Method::GET, url))
+            .await?;
+
+        Ok(response.bytes().await?)
+    }
+
+    /// Download the content of an uploaded file to the given path. The content is streamed to disk as it is received.
+    ///
+    /// # Arguments
+    ///
+    /// * `file_id` - The id of the file.
+    ///
+    /// * `path` - The path to write the content to. An existing file is overwritten.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the number of bytes written or an error. On error, the partially written file is removed.
+    pub async fn download_file(
+        &self,
+        file_id: impl AsRef<str>,
+        path: impl AsRef<Path>,
+    ) -> Result<u64, LlamaEdgeError> {
+        let url = self.file_url(file_id.as_ref(), Some("content"))?;
+        let response = self
+            .send(Endpoint::Files, self.request(Method::GET, url))
+            .await?;
+
+        let path = path.as_ref();
+        let mut file = tokio::fs::File::create(path).await.map_err(|e| {
+            LlamaEdgeError::Operation(format!("Failed to create {}: {}", path.display(), e))
+        })?;
+
+        let mut written = 0;
+        let mut stream = response.bytes_stream();
+        let result = async {
+            while let Some(chunk) = stream.next().await {
+                let chunk = chunk?;
+                file.write_all(&chunk).await.map_err(|e| {
+                    LlamaEdgeError::Operation(format!("Failed to write {}: {}", path.display(), e))
+                })?;
+                written += chunk.len() as u64;
+            }
+            file.flush().await.map_err(|e| {
+                LlamaEdgeError::Operation(format!("Failed to write {}: {}", path.display(), e))
+            })
+        }
+        .await;
+
+        match result {
+            Ok(()) => Ok(written),
+            Err(e) => {
+                drop(file);
+                let _ = tokio::fs::remove_file(path).await;
+                Err(e)
+            }
+        }
+    }
+
+    /// Delete an uploaded file from the server.
+    ///
+    /// # Arguments
+    ///
+    /// * `file_id` - The id of the file.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the deletion status or an error.
+    pub async fn delete_file(
+        &self,
+        file_id: impl AsRef<str>,
+    ) -> Result<DeleteFileStatus, LlamaEdgeError> {
+        let url = self.file_url(file_id.as_ref(), None)?;
+        let response = self
+            .send(Endpoint::Files, self.request(Method::DELETE, url))
+            .await?;
+
+        decode_json::<DeleteFileStatus>(response).await
+    }
+
     /// List all available models.
     ///
     /// # Returns
     ///
     /// A `Result` containing the list of models or an error.
     pub async fn models(&self) -> Result<Vec<Model>, LlamaEdgeError> {
-        let url = self.server_base_url.join("/v1/models")?;
-        let response = reqwest::Client::new()
-            .get(url)
-            .send()
-            .await
-            .map_err(|e| LlamaEdgeError::Operation(e.to_string()))?;
-        let list_models_response = response
-            .json::<ListModelsResponse>()
-            .await
-            .map_err(|e| LlamaEdgeError::Operation(e.to_string()))?;
+        let url = self.endpoint_url(Endpoint::Models)?;
+        let response = self
+            .send(Endpoint::Models, self.request(Method::GET, url))
+            .await?;
+        let list_models_response = decode_json::<ListModelsResponse>(response).await?;
 
         Ok(list_models_response.data)
     }
@@ -347,7 +738,7 @@ impl Client {
         input: InputText,
         params: EmbeddingsParams,
     ) -> Result<EmbeddingsResponse, LlamaEdgeError> {
-        let url = self.server_base_url.join("/v1/embeddings")?;
+        let url = self.endpoint_url(Endpoint::Embeddings)?;
 
         let request = EmbeddingRequest {
             input,
@@ -362,17 +753,14 @@ impl Client {
             vdb_api_key: params.vdb_api_key,
         };
 
-        let response = reqwest::Client::new()
-            .post(url)
-            .json(&request)
-            .send()
-            .await
-            .map_err(|e| LlamaEdgeError::Operation(e.to_string()))?;
+        let response = self
+            .send(
+                Endpoint::Embeddings,
+                self.request(Method::POST, url).json(&request),
+            )
+            .await?;
 
-        let embeddings_response = response
-            .json::<EmbeddingsResponse>()
-            .await
-            .map_err(|e| LlamaEdgeError::Operation(e.to_string()))?;
+        let embeddings_response = decode_json::<EmbeddingsResponse>(response).await?;
 
         Ok(embeddings_response)
     }
@@ -381,7 +769,7 @@ impl Client {
     ///
     /// # Arguments
     ///
-    /// * `audio_file` - The audio file to transcribe.
+    /// * `audio_file` - The path to the audio file to transcribe, or an [`UploadFile`] to send it from memory or an async reader, or to set its content type explicitly.
     ///
     /// * `spoken_language` - The language of the audio file. The language should be in [ISO-639-1](https://en.wikipedia.org/wiki/List_of_ISO_639-1_codes) format. For example, "en" for English, "zh" for Chinese, "ja" for Japanese, etc.
     ///
@@ -389,54 +777,22 @@ impl Client {
     ///
     /// # Returns
     ///
-    /// A `Result` containing the transcription object or an error.
+    /// A `Result` containing the transcript in the format requested by `params.response_format`, or an error.
     #[cfg(feature = "audio")]
     pub async fn transcribe(
         &self,
-        audio_file: impl AsRef<Path>,
+        audio_file: impl Into<UploadFile>,
         spoken_language: impl AsRef<str>,
         params: TranscriptionParams,
-    ) -> Result<TranscriptionObject, LlamaEdgeError> {
-        let abs_file_path = if audio_file.as_ref().is_absolute() {
-            audio_file.as_ref().to_path_buf()
+    ) -> Result<Transcript, LlamaEdgeError> {
+        let audio_file = if params.preprocess_audio {
+            audio::wav::prepare_upload(audio_file.into()).await?
         } else {
-            std::env::current_dir().unwrap().join(audio_file.as_ref())
+            audio_file.into()
         };
-
-        // check if the file exists
-        if !abs_file_path.exists() {
-            let error_message =
-                format!("The audio file does not exist: {}", abs_file_path.display());
-
-            return Err(LlamaEdgeError::InvalidArgument(error_message));
-        }
-
-        // get the filename
-        let filename = abs_file_path
-            .file_name()
-            .unwrap()
-            .to_str()
-            .unwrap()
-            .to_string();
-
-        // get the file extension
-        let file_extension = abs_file_path
-            .extension()
-            .unwrap()
-            .to_str()
-            .unwrap()
-            .to_string();
-
-        let file = tokio::fs::read(abs_file_path).await.map_err(|e| {
-            LlamaEdgeError::Operation(format!("Failed to read the audio file: {}", e))
-        })?;
+        let file_part = audio_file.into_part("audio file").await?;
 
         let form = {
-            let file_part = multipart::Part::bytes(file)
-                .file_name(filename)
-                .mime_str(&format!("audio/{}", file_extension))
-                .map_err(|e| LlamaEdgeError::Operation(e.to_string()))?;
-
             let language = if spoken_language.as_ref().is_empty() {
                 "en".to_string()
             } else {
@@ -446,7 +802,7 @@ impl Client {
                 .mime_str("text/plain")
                 .map_err(|e| LlamaEdgeError::Operation(e.to_string()))?;
 
-            let response_format_part = multipart::Part::text(params.response_format)
+            let response_format_part = multipart::Part::text(params.response_format.to_string())
                 .mime_str("text/plain")
                 .map_err(|e| LlamaEdgeError::Operation(e.to_string()))?;
 
@@ -509,32 +865,159 @@ impl Client {
                 form = form.part("prompt", prompt_part);
             }
 
+            // timestamp granularities are only supported by the `verbose_json` format
+            if params.response_format == TranscriptionFormat::VerboseJson {
+                for granularity in params.timestamp_granularities.iter().flatten() {
+                    let granularity = serde_json::to_value(granularity)
+                        .ok()
+                        .and_then(|value| value.as_str().map(|s| s.to_string()))
+                        .unwrap_or_default();
+                    let granularity_part = multipart::Part::text(granularity)
+                        .mime_str("text/plain")
+                        .map_err(|e| LlamaEdgeError::Operation(e.to_string()))?;
+                    form = form.part("timestamp_granularities[]", granularity_part);
+                }
+            }
+
             form
         };
 
         // send the transcription request
-        let url = self.server_base_url.join("/v1/audio/transcriptions")?;
-        let response = reqwest::Client::new()
-            .post(url)
-            .multipart(form)
-            .send()
-            .await
-            .map_err(|e| LlamaEdgeError::Operation(e.to_string()))?;
+        let url = self.endpoint_url(Endpoint::AudioTranscriptions)?;
+        let response = self
+            .send(
+                Endpoint::AudioTranscriptions,
+                self.request(Method::POST, url).multipart(form),
+            )
+            .await?;
+
+        // get the transcript in the requested format
+        decode_transcript(
+            response,
+            params.response_format,
+            |transcription_object: TranscriptionObject| transcription_object.text,
+        )
+        .await
+    }
 
-        // get the transcription object
-        let transcription_object = response
-            .json::<TranscriptionObject>()
-            .await
-            .map_err(|e| LlamaEdgeError::Operation(e.to_string()))?;
+    /// Transcribe a WAV recording longer than the server can process in one request.
+    ///
+    /// The recording is split into overlapping windows, which are transcribed in the `verbose_json` format, concurrently unless the prompt is carried forward. The transcripts of the windows are then stitched into one, with the timestamps relative to the start of the recording.
+    ///
+    /// A [`VerboseTranscript`] is returned rather than a `TranscriptionObject`, which only carries the text, so that the stitched segment and word timestamps are not lost. It converts into a `TranscriptionObject` with [`From`].
+    ///
+    /// # Arguments
+    ///
+    /// * `audio_file` - The WAV file to transcribe.
+    ///
+    /// * `spoken_language` - The language of the audio file. The language should be in [ISO-639-1](https://en.wikipedia.org/wiki/List_of_ISO_639-1_codes) format.
+    ///
+    /// * `params` - The parameters for the transcription of each window. `response_format`, `offset_time` and `duration` are ignored.
+    ///
+    /// * `options` - The options for splitting the recording.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the stitched transcript or an error. An error is returned if the recording is empty, or if the transcription of any window fails.
+    #[cfg(feature = "audio")]
+    pub async fn transcribe_long(
+        &self,
+        audio_file: impl AsRef<Path>,
+        spoken_language: impl AsRef<str>,
+        params: TranscriptionParams,
+        options: LongTranscriptionOptions,
+    ) -> Result<VerboseTranscript, LlamaEdgeError> {
+        options.validate()?;
+
+        let audio_file = audio_file.as_ref();
+        if !audio_file.is_file() {
+            return Err(LlamaEdgeError::InvalidArgument(format!(
+                "The audio file does not exist: {}",
+                audio_file.display()
+            )));
+        }
+        let bytes = tokio::fs::read(audio_file).await.map_err(|e| {
+            LlamaEdgeError::Operation(format!("Failed to read the audio file: {}", e))
+        })?;
+        let bytes = if params.preprocess_audio {
+            audio::wav::to_whisper_wav_blocking(bytes).await?
+        } else {
+            bytes
+        };
+        let wav = Wav::parse(&bytes)?;
+
+        let duration = wav.duration();
+        if duration.is_zero() {
+            return Err(LlamaEdgeError::InvalidArgument(
+                "The audio file contains no audio".to_string(),
+            ));
+        }
+        let windows = options.windows(duration);
+        let chunks = windows
+            .iter()
+            .map(|window| wav.slice(window.start, window.end))
+            .collect::<Vec<_>>();
+
+        // the texts of the finished chunks, each used as the prompt of the next one
+        let texts = std::sync::Mutex::new(std::collections::BTreeMap::new());
+        let spoken_language = spoken_language.as_ref();
+        let transcribe_chunk = |i: usize, chunk: Vec<u8>| {
+            let mut params = TranscriptionParams {
+                response_format: TranscriptionFormat::VerboseJson,
+                offset_time: 0,
+                duration: 0,
+                // the recording is converted once before it is split
+                preprocess_audio: false,
+                ..params.clone()
+            };
+            let texts = &texts;
+            async move {
+                if options.carry_prompt() {
+                    // the chunks are transcribed one after the other, so the previous one has finished
+                    let previous = i.checked_sub(1).and_then(|previous| {
+                        texts
+                            .lock()
+                            .unwrap_or_else(|e| e.into_inner())
+                            .get(&previous)
+                            .map(|text: &String| long::prompt_from(text))
+                    });
+                    if previous.is_some() {
+                        params.prompt = previous;
+                    }
+                }
+
+                let file = UploadFile::from_bytes(chunk, format!("chunk_{}.wav", i));
+                let transcript = match self.transcribe(file, spoken_language, params).await? {
+                    Transcript::Verbose(verbose) => verbose,
+                    other => VerboseTranscript {
+                        text: other.text().to_string(),
+                        ..Default::default()
+                    },
+                };
+
+                texts
+                    .lock()
+                    .unwrap_or_else(|e| e.into_inner())
+                    .insert(i, transcript.text.clone());
+
+                Ok::<_, LlamaEdgeError>(transcript)
+            }
+        };
+
+        let transcripts = futures::stream::iter(chunks.into_iter().enumerate())
+            .map(|(i, chunk)| transcribe_chunk(i, chunk))
+            .buffered(options.effective_concurrency())
+            .try_collect::<Vec<_>>()
+            .await?;
 
-        Ok(transcription_object)
+        Ok(options.stitch(&windows, transcripts, duration))
     }
 
     /// Translate an audio file.
     ///
     /// # Arguments
     ///
-    /// * `audio_file` - The audio file to translate.
+    /// * `audio_file` - The path to the audio file to translate, or an [`UploadFile`] to send it from memory or an async reader, or to set its content type explicitly.
     ///
     /// * `spoken_language` - The language of the audio file. The language should be in [ISO-639-1](https://en.wikipedia.org/wiki/List_of_ISO_639-1_codes) format. For example, "en" for English, "zh" for Chinese, "ja" for Japanese, etc.
     ///
@@ -542,55 +1025,23 @@ impl Client {
     ///
     /// # Returns
     ///
-    /// A `Result` containing the translation object or an error.
+    /// A `Result` containing the translation in the format requested by `params.response_format`, or an error.
     #[cfg(feature = "audio")]
     pub async fn translate(
         &self,
-        audio_file: impl AsRef<Path>,
+        audio_file: impl Into<UploadFile>,
         spoken_language: impl AsRef<str>,
         params: TranslationParams,
-    ) -> Result<TranslationObject, LlamaEdgeError> {
-        let abs_file_path = if audio_file.as_ref().is_absolute() {
-            audio_file.as_ref().to_path_buf()
+    ) -> Result<Transcript, LlamaEdgeError> {
+        let audio_file = if params.preprocess_audio {
+            audio::wav::prepare_upload(audio_file.into()).await?
         } else {
-            std::env::current_dir().unwrap().join(audio_file.as_ref())
+            audio_file.into()
         };
-
-        // check if the file exists
-        if !abs_file_path.exists() {
-            let error_message =
-                format!("The audio file does not exist: {}", abs_file_path.display());
-
-            return Err(LlamaEdgeError::InvalidArgument(error_message));
-        }
-
-        // get the filename
-        let filename = abs_file_path
-            .file_name()
-            .unwrap()
-            .to_str()
-            .unwrap()
-            .to_string();
-
-        // get the file extension
-        let file_extension = abs_file_path
-            .extension()
-            .unwrap()
-            .to_str()
-            .unwrap()
-            .to_string();
-
-        let file = tokio::fs::read(abs_file_path)
-            .await
-            .map_err(|e| LlamaEdgeError::Operation(format!("Failed to read audio file: {}", e)))?;
+        let file_part = audio_file.into_part("audio file").await?;
 
         let form = {
-            let file_part = multipart::Part::bytes(file)
-                .file_name(filename)
-                .mime_str(&format!("audio/{}", file_extension))
-                .map_err(|e| LlamaEdgeError::Operation(e.to_string()))?;
-
-            let response_format_part = multipart::Part::text(params.response_format)
+            let response_format_part = multipart::Part::text(params.response_format.to_string())
                 .mime_str("text/plain")
                 .map_err(|e| LlamaEdgeError::Operation(e.to_string()))?;
 
@@ -666,21 +1117,21 @@ impl Client {
         };
 
         // send the transcription request
-        let url = self.server_base_url.join("/v1/audio/translations")?;
-        let response = reqwest::Client::new()
-            .post(url)
-            .multipart(form)
-            .send()
-            .await
-            .map_err(|e| LlamaEdgeError::Operation(e.to_string()))?;
-
-        // get the translation object
-        let translation_object = response
-            .json::<TranslationObject>()
-            .await
-            .map_err(|e| LlamaEdgeError::Operation(e.to_string()))?;
-
-        Ok(translation_object)
+        let url = self.endpoint_url(Endpoint::AudioTranslations)?;
+        let response = self
+            .send(
+                Endpoint::AudioTranslations,
+                self.request(Method::POST, url).multipart(form),
+            )
+            .await?;
+
+        // get the translation in the requested format
+        decode_transcript(
+            response,
+            params.response_format,
+            |translation_object: TranslationObject| translation_object.text,
+        )
+        .await
     }
 
     /// Create an image with the given prompt.
@@ -700,7 +1151,7 @@ impl Client {
         prompt: impl AsRef<str>,
         params: ImageCreateParams,
     ) -> Result<Vec<ImageObject>, LlamaEdgeError> {
-        let url = self.server_base_url.join("/v1/images/generations")?;
+        let url = self.endpoint_url(Endpoint::ImageGenerations)?;
 
         // build the request
         let mut builder = ImageCreateRequestBuilder::new(params.model, prompt.as_ref())
@@ -723,22 +1174,19 @@ impl Client {
             builder = builder.with_user(user);
         }
         if let Some(control_image) = params.control_image {
-            builder = builder.with_control_image(control_image);
+            builder = builder.with_control_image(self.image_file_object(control_image).await?);
         }
         let request = builder.build();
 
         // send the request
-        let response = reqwest::Client::new()
-            .post(url)
-            .json(&request)
-            .send()
-            .await
-            .map_err(|e| LlamaEdgeError::Operation(e.to_string()))?;
+        let response = self
+            .send(
+                Endpoint::ImageGenerations,
+                self.request(Method::POST, url).json(&request),
+            )
+            .await?;
 
-        let list_images_response = response
-            .json::<ListImagesResponse>()
-            .await
-            .map_err(|e| LlamaEdgeError::Operation(e.to_string()))?;
+        let list_images_response = decode_json::<ListImagesResponse>(response).await?;
 
         Ok(list_images_response.data)
     }
@@ -747,7 +1195,7 @@ impl Client {
     ///
     /// # Arguments
     ///
-    /// * `image` - The image to edit.
+    /// * `image` - The image to edit: the path to a local file, its content held in memory, [`UploadFile`] such as one read from an async reader, or a file previously uploaded with [`Client::upload_file`].
     ///
     /// * `prompt` - The prompt for the image edit.
     ///
@@ -755,257 +1203,269 @@ impl Client {
     ///
     /// # Returns
     ///
-    /// A `Result` containing the list of images or an error.
+    /// A `Result` containing the list of images or an error. If the dimensions of the mask differ from those of the image, an error is returned before the request is sent.
     #[cfg(feature = "image")]
     pub async fn edit_image(
         &self,
-        image: impl AsRef<Path>,
+        image: impl Into<ImageInput>,
         prompt: impl AsRef<str>,
         params: ImageEditParams,
     ) -> Result<Vec<ImageObject>, LlamaEdgeError> {
-        let abs_file_path = if image.as_ref().is_absolute() {
-            image.as_ref().to_path_buf()
-        } else {
-            std::env::current_dir().unwrap().join(image.as_ref())
+        self.send_image_form(
+            Endpoint::ImageEdits,
+            image.into(),
+            Some(prompt.as_ref()),
+            params,
+        )
+        .await
+    }
+
+    /// Create variations of the given image.
+    ///
+    /// Set `params.n` to get several variations in one request, and `params.seed` to get the same variations for the same image.
+    ///
+    /// # Arguments
+    ///
+    /// * `image` - The image to vary: the path to a local file, its content held in memory, [`UploadFile`] such as one read from an async reader, or a file previously uploaded with [`Client::upload_file`].
+    ///
+    /// * `params` - The parameters for the variations. `mask` is ignored.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the list of images or an error.
+    #[cfg(feature = "image")]
+    pub async fn create_image_variation(
+        &self,
+        image: impl Into<ImageInput>,
+        params: ImageEditParams,
+    ) -> Result<Vec<ImageObject>, LlamaEdgeError> {
+        let params = ImageEditParams {
+            mask: None,
+            ..params
         };
 
-        // check if the file exists
-        if !abs_file_path.exists() {
-            let error_message =
-                format!("The image file does not exist: {}", abs_file_path.display());
+        self.send_image_form(Endpoint::ImageVariations, image.into(), None, params)
+            .await
+    }
 
-            return Err(LlamaEdgeError::InvalidArgument(error_message));
+    /// Generate an image from a source image and a prompt (img2img).
+    ///
+    /// The whole source image is regenerated, following the prompt. This is sent to the image edit endpoint without a mask, which the server handles as image-to-image generation.
+    ///
+    /// # Arguments
+    ///
+    /// * `image` - The source image: the path to a local file, its content held in memory, [`UploadFile`] such as one read from an async reader, or a file previously uploaded with [`Client::upload_file`].
+    ///
+    /// * `prompt` - The prompt for the generation.
+    ///
+    /// * `strength` - How much the source image is noised before it is regenerated, between 0 and 1. Low values keep the output close to the source image, and `1.0` ignores it. It overrides `params.strength`.
+    ///
+    /// * `params` - The parameters for the generation. `mask` is ignored.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the list of images or an error.
+    #[cfg(feature = "image")]
+    pub async fn image_to_image(
+        &self,
+        image: impl Into<ImageInput>,
+        prompt: impl AsRef<str>,
+        strength: f32,
+        params: ImageEditParams,
+    ) -> Result<Vec<ImageObject>, LlamaEdgeError> {
+        if !(0.0..=1.0).contains(&strength) {
+            return Err(LlamaEdgeError::InvalidArgument(format!(
+                "The strength must be between 0 and 1, got {}",
+                strength
+            )));
         }
+        let params = ImageEditParams {
+            mask: None,
+            strength,
+            ..params
+        };
 
-        // get the filename
-        let filename = abs_file_path
-            .file_name()
-            .unwrap()
-            .to_str()
-            .unwrap()
-            .to_string();
-
-        // get the file extension
-        let file_extension = abs_file_path
-            .extension()
-            .unwrap()
-            .to_str()
-            .unwrap()
-            .to_string();
-
-        let file = tokio::fs::read(abs_file_path).await.map_err(|e| {
-            LlamaEdgeError::Operation(format!("Failed to read the image file: {}", e))
-        })?;
-
-        let form = {
-            let file_part = multipart::Part::bytes(file)
-                .file_name(filename)
-                .mime_str(&format!("image/{}", file_extension))
-                .map_err(|e| LlamaEdgeError::Operation(e.to_string()))?;
-
-            let prompt_part = multipart::Part::text(prompt.as_ref().to_string())
-                .mime_str("text/plain")
-                .map_err(|e| LlamaEdgeError::Operation(e.to_string()))?;
-
-            let model_part = multipart::Part::text(params.model.clone())
-                .mime_str("text/plain")
-                .map_err(|e| LlamaEdgeError::Operation(e.to_string()))?;
-
-            let n_part = multipart::Part::text(params.n.to_string())
-                .mime_str("text/plain")
-                .map_err(|e| LlamaEdgeError::Operation(e.to_string()))?;
-
-            let response_format_part = multipart::Part::text(params.response_format.to_string())
-                .mime_str("text/plain")
-                .map_err(|e| LlamaEdgeError::Operation(e.to_string()))?;
-
-            let cfg_scale_part = multipart::Part::text(params.cfg_scale.to_string())
-                .mime_str("text/plain")
-                .map_err(|e| LlamaEdgeError::Operation(e.to_string()))?;
-
-            let sample_method_part = multipart::Part::text(params.sample_method.to_string())
-                .mime_str("text/plain")
-                .map_err(|e| LlamaEdgeError::Operation(e.to_string()))?;
-
-            let steps_part = multipart::Part::text(params.steps.to_string())
-                .mime_str("text/plain")
-                .map_err(|e| LlamaEdgeError::Operation(e.to_string()))?;
-
-            let height_part = multipart::Part::text(params.height.to_string())
-                .mime_str("text/plain")
-                .map_err(|e| LlamaEdgeError::Operation(e.to_string()))?;
-
-            let width_part = multipart::Part::text(params.width.to_string())
-                .mime_str("text/plain")
-                .map_err(|e| LlamaEdgeError::Operation(e.to_string()))?;
-
-            let control_strength_part = multipart::Part::text(params.control_strength.to_string())
-                .mime_str("text/plain")
-                .map_err(|e| LlamaEdgeError::Operation(e.to_string()))?;
-
-            let seed_part = multipart::Part::text(params.seed.to_string())
-                .mime_str("text/plain")
-                .map_err(|e| LlamaEdgeError::Operation(e.to_string()))?;
-
-            let strength_part = multipart::Part::text(params.strength.to_string())
-                .mime_str("text/plain")
-                .map_err(|e| LlamaEdgeError::Operation(e.to_string()))?;
-
-            let scheduler_part = multipart::Part::text(params.scheduler.to_string())
-                .mime_str("text/plain")
-                .map_err(|e| LlamaEdgeError::Operation(e.to_string()))?;
-
-            let apply_canny_preprocessor_part =
-                multipart::Part::text(params.apply_canny_preprocessor.to_string())
-                    .mime_str("text/plain")
-                    .map_err(|e| LlamaEdgeError::Operation(e.to_string()))?;
+        self.send_image_form(
+            Endpoint::ImageEdits,
+            image.into(),
+            Some(prompt.as_ref()),
+            params,
+        )
+        .await
+    }
 
-            let style_ratio_part = multipart::Part::text(params.style_ratio.to_string())
-                .mime_str("text/plain")
-                .map_err(|e| LlamaEdgeError::Operation(e.to_string()))?;
+    /// Send an image with the parameters of an image edit as a multipart form, and decode the returned images.
+    #[cfg(feature = "image")]
+    async fn send_image_form(
+        &self,
+        endpoint: Endpoint,
+        image: ImageInput,
+        prompt: Option<&str>,
+        params: ImageEditParams,
+    ) -> Result<Vec<ImageObject>, LlamaEdgeError> {
+        let mut image_file = self.image_upload(image, "image file").await?;
+
+        // the image is only buffered when its dimensions are checked against those of the mask
+        let mut mask_file = None;
+        if let Some(mask) = params.mask {
+            let (image, image_bytes) = image_file.buffer("image file").await?;
+            let (mask, mask_bytes) = self
+                .image_upload(mask, "mask image file")
+                .await?
+                .buffer("mask image file")
+                .await?;
+            image::validate_mask(&image_bytes, &mask_bytes)?;
+
+            image_file = image;
+            mask_file = Some(mask);
+        }
 
-            let mut form = multipart::Form::new()
-                .part("file", file_part)
-                .part("prompt", prompt_part)
-                .part("model", model_part)
-                .part("n", n_part)
-                .part("response_format", response_format_part)
-                .part("cfg_scale", cfg_scale_part)
-                .part("sample_method", sample_method_part)
-                .part("steps", steps_part)
-                .part("height", height_part)
-                .part("width", width_part)
-                .part("control_strength", control_strength_part)
-                .part("seed", seed_part)
-                .part("strength", strength_part)
-                .part("scheduler", scheduler_part)
-                .part("apply_canny_preprocessor", apply_canny_preprocessor_part)
-                .part("style_ratio", style_ratio_part);
-
-            if let Some(user) = params.user {
-                let user_part = multipart::Part::text(user)
-                    .mime_str("text/plain")
-                    .map_err(|e| LlamaEdgeError::Operation(e.to_string()))?;
-                form = form.part("user", user_part);
-            }
+        let mut form =
+            multipart::Form::new().part("file", image_file.into_part("image file").await?);
+        if let Some(prompt) = prompt {
+            form = form.part("prompt", text_part(prompt.to_string())?);
+        }
+        for (name, value) in [
+            ("model", params.model),
+            ("n", params.n.to_string()),
+            ("response_format", params.response_format.to_string()),
+            ("cfg_scale", params.cfg_scale.to_string()),
+            ("sample_method", params.sample_method.to_string()),
+            ("steps", params.steps.to_string()),
+            ("height", params.height.to_string()),
+            ("width", params.width.to_string()),
+            ("control_strength", params.control_strength.to_string()),
+            ("seed", params.seed.to_string()),
+            ("strength", params.strength.to_string()),
+            ("scheduler", params.scheduler.to_string()),
+            (
+                "apply_canny_preprocessor",
+                params.apply_canny_preprocessor.to_string(),
+            ),
+            ("style_ratio", params.style_ratio.to_string()),
+        ] {
+            form = form.part(name, text_part(value)?);
+        }
+        if let Some(user) = params.user {
+            form = form.part("user", text_part(user)?);
+        }
+        if let Some(negative_prompt) = params.negative_prompt {
+            form = form.part("negative_prompt", text_part(negative_prompt)?);
+        }
+        if let Some(mask_file) = mask_file {
+            form = form.part("mask", mask_file.into_part("mask image file").await?);
+        }
+        if let Some(control_image) = params.control_image {
+            let control_image_file = self
+                .image_upload(control_image, "control image file")
+                .await?;
+            form = form.part(
+                "control_image",
+                control_image_file.into_part("control image file").await?,
+            );
+        }
 
-            if let Some(negative_prompt) = params.negative_prompt {
-                let negative_prompt_part = multipart::Part::text(negative_prompt)
-                    .mime_str("text/plain")
-                    .map_err(|e| LlamaEdgeError::Operation(e.to_string()))?;
-                form = form.part("negative_prompt", negative_prompt_part);
-            }
+        let url = self.endpoint_url(endpoint)?;
 
-            if let Some(mask) = params.mask {
-                let abs_mask_file_path = if mask.is_absolute() {
-                    mask.to_path_buf()
-                } else {
-                    std::env::current_dir().unwrap().join(mask)
-                };
+        let response = self
+            .send(endpoint, self.request(Method::POST, url).multipart(form))
+            .await?;
 
-                // check if the file exists
-                if !abs_mask_file_path.exists() {
-                    let error_message = format!(
-                        "The mask image file does not exist: {}",
-                        abs_mask_file_path.display()
-                    );
+        let list_images_response = decode_json::<ListImagesResponse>(response).await?;
 
-                    return Err(LlamaEdgeError::InvalidArgument(error_message));
-                }
+        Ok(list_images_response.data)
+    }
 
-                // get the filename
-                let mask_filename = abs_mask_file_path
-                    .file_name()
-                    .unwrap()
-                    .to_str()
-                    .unwrap()
-                    .to_string();
-
-                // get the file extension
-                let mask_file_extension = abs_mask_file_path
-                    .extension()
-                    .unwrap()
-                    .to_str()
-                    .unwrap()
-                    .to_string();
-
-                let mask_file = tokio::fs::read(abs_mask_file_path).await.map_err(|e| {
-                    LlamaEdgeError::Operation(format!("Failed to read the image file: {}", e))
+    /// Get a file to send in a multipart request from an image input. The content of an uploaded file is downloaded from the server.
+    #[cfg(feature = "image")]
+    async fn image_upload(
+        &self,
+        image: ImageInput,
+        kind: &str,
+    ) -> Result<UploadFile, LlamaEdgeError> {
+        match image {
+            ImageInput::Path(path) => Ok(UploadFile::from_path(path)),
+            ImageInput::Bytes { bytes, filename } => Ok(UploadFile::from_bytes(bytes, filename)),
+            ImageInput::Upload(file) => Ok(file),
+            ImageInput::File(file) => {
+                let bytes = self.retrieve_file_content(&file.id).await.map_err(|e| {
+                    LlamaEdgeError::Operation(format!("Failed to download the {}: {}", kind, e))
                 })?;
-
-                let mask_file_part = multipart::Part::bytes(mask_file)
-                    .file_name(mask_filename)
-                    .mime_str(&format!("image/{}", mask_file_extension))
-                    .map_err(|e| LlamaEdgeError::Operation(e.to_string()))?;
-
-                form = form.part("mask", mask_file_part);
+                Ok(UploadFile::from_bytes(bytes, file.filename))
             }
+        }
+    }
 
-            if let Some(control_image) = params.control_image {
-                let abs_control_image_file_path = if control_image.is_absolute() {
-                    control_image.to_path_buf()
-                } else {
-                    std::env::current_dir().unwrap().join(control_image)
-                };
-
-                // check if the file exists
-                if !abs_control_image_file_path.exists() {
-                    let error_message = format!(
-                        "The control image file does not exist: {}",
-                        abs_control_image_file_path.display()
-                    );
-
-                    return Err(LlamaEdgeError::InvalidArgument(error_message));
-                }
-
-                // get the filename
-                let control_image_filename = abs_control_image_file_path
-                    .file_name()
-                    .unwrap()
-                    .to_str()
-                    .unwrap()
-                    .to_string();
-
-                // get the file extension
-                let control_image_file_extension = abs_control_image_file_path
-                    .extension()
-                    .unwrap()
-                    .to_str()
-                    .unwrap()
-                    .to_string();
-
-                let control_image_file = tokio::fs::read(abs_control_image_file_path)
+    /// Get a file on the server from an image input. A local image is uploaded first.
+    #[cfg(feature = "image")]
+    async fn image_file_object(&self, image: ImageInput) -> Result<FileObject, LlamaEdgeError> {
+        match image {
+            ImageInput::Path(path) => self.upload_file(path).await,
+            ImageInput::Bytes { bytes, filename } => {
+                self.upload_file(UploadFile::from_bytes(bytes, filename))
                     .await
-                    .map_err(|e| {
-                        LlamaEdgeError::Operation(format!("Failed to read the image file: {}", e))
-                    })?;
-
-                let control_image_file_part = multipart::Part::bytes(control_image_file)
-                    .file_name(control_image_filename)
-                    .mime_str(&format!("image/{}", control_image_file_extension))
-                    .map_err(|e| LlamaEdgeError::Operation(e.to_string()))?;
-
-                form = form.part("control_image", control_image_file_part);
             }
+            ImageInput::Upload(file) => self.upload_file(file).await,
+            ImageInput::File(file) => Ok(file),
+        }
+    }
 
-            form
+    /// Get the raw content of an image returned by [`Client::create_image`] or [`Client::edit_image`].
+    ///
+    /// Images returned in the `b64_json` format are decoded. Images returned in the `url` format are downloaded with this client. The configured credentials are sent only if the URL is on the same origin as the server base URL.
+    ///
+    /// # Arguments
+    ///
+    /// * `image` - The image returned by the server.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the content of the image and its detected format, or an error.
+    #[cfg(feature = "image")]
+    pub async fn image_bytes(&self, image: &ImageObject) -> Result<ImageData, LlamaEdgeError> {
+        let url = match ImageSource::of(image)? {
+            ImageSource::Embedded(bytes) => return Ok(ImageData::new(bytes)),
+            ImageSource::Url(url) => url,
+        };
+
+        // relative URLs are resolved against the server base URL
+        let url = self.server_base_url.join(url)?;
+        let request = if url.origin() == self.server_base_url.origin() {
+            self.request(Method::GET, url)
+        } else {
+            self.http_client.get(url)
         };
 
-        let url = self.server_base_url.join("/v1/images/edits")?;
+        let response = Self::send_once(request).await?;
+        let bytes = response.bytes().await?;
 
-        let response = reqwest::Client::new()
-            .post(url)
-            .multipart(form)
-            .send()
-            .await
-            .map_err(|e| LlamaEdgeError::Operation(e.to_string()))?;
+        Ok(ImageData::new(bytes))
+    }
 
-        let list_images_response = response
-            .json::<ListImagesResponse>()
+    /// Save an image returned by [`Client::create_image`] or [`Client::edit_image`] to the given path, as is. See [`Client::image_bytes`] for how its content is obtained.
+    ///
+    /// # Arguments
+    ///
+    /// * `image` - The image returned by the server.
+    ///
+    /// * `path` - The path to save the image to. The file is overwritten if it exists.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the content of the image and its detected format, or an error.
+    #[cfg(feature = "image")]
+    pub async fn save_image(
+        &self,
+        image: &ImageObject,
+        path: impl AsRef<Path>,
+    ) -> Result<ImageData, LlamaEdgeError> {
+        let image_data = self.image_bytes(image).await?;
+
+        tokio::fs::write(path.as_ref(), image_data.bytes())
             .await
-            .map_err(|e| LlamaEdgeError::Operation(e.to_string()))?;
+            .map_err(|e| LlamaEdgeError::Operation(format!("Failed to save the image: {}", e)))?;
 
-        Ok(list_images_response.data)
+        Ok(image_data)
     }
 
     /// Retrieve the context from the VectorDB server.
@@ -1025,59 +1485,139 @@ impl Client {
         chat_history: &[ChatCompletionRequestMessage],
         params: RagChatParams,
     ) -> Result<Vec<RetrieveObject>, LlamaEdgeError> {
-        let url = self.server_base_url.join("/v1/retrieve")?;
+        let request = rag_chat_request(chat_history, params)?;
 
-        // build the request
-        let mut builder = ChatCompletionRequestBuilder::new(chat_history)
-            .with_n_choices(params.n_choice)
-            .with_max_completion_tokens(params.max_completion_tokens)
-            .with_presence_penalty(params.presence_penalty)
-            .with_frequency_penalty(params.frequency_penalty)
-            .with_rag_context_window(params.context_window);
-
-        if let Some(model) = params.model {
-            builder = builder.with_model(model);
-        }
-        if let Some(user) = params.user {
-            builder = builder.with_user(user);
-        }
-        if let Some(response_format) = params.response_format {
-            builder = builder.with_reponse_format(response_format);
-        }
-        if let Some(tools) = params.tools {
-            builder = builder.with_tools(tools);
-        }
-        if let Some(tool_choice) = params.tool_choice {
-            builder = builder.with_tool_choice(tool_choice);
+        self.send_rag_retrieve(&request).await
+    }
+
+    /// Send a RAG chat completion request.
+    ///
+    /// The server retrieves the context relevant to the last user messages from the VectorDB server, and answers with it. The server does not return that context, so if `params.retrieve_sources` is set, the sources are retrieved with a second, concurrent request to `/v1/retrieve` with the same parameters. They are only approximate: they usually match the context of the answer, but are not guaranteed to, for example if the collection changes between the two retrievals.
+    ///
+    /// # Arguments
+    ///
+    /// * `chat_history` - The chat history including the latest user message.
+    ///
+    /// * `params` - The parameters for the retrieval and the chat completion.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the chat completion together with the retrieved context, or an error. The context is empty unless `params.retrieve_sources` is set.
+    #[cfg(feature = "rag")]
+    pub async fn rag_chat(
+        &self,
+        chat_history: &[ChatCompletionRequestMessage],
+        params: RagChatParams,
+    ) -> Result<RagChatCompletion, LlamaEdgeError> {
+        if chat_history.is_empty() {
+            return Err(LlamaEdgeError::InvalidArgument(
+                "chat_history cannot be empty".to_string(),
+            ));
         }
-        if let Some(vdb_config) = params.vdb_config {
-            builder = builder.with_rag_vdb_settings(
-                vdb_config.server_url,
-                vdb_config.collection_name,
-                vdb_config.limit,
-                vdb_config.score_threshold,
-                vdb_config.api_key,
-            );
+
+        let retrieve_sources = params.retrieve_sources;
+        let request = rag_chat_request(chat_history, params)?;
+
+        let url = self.endpoint_url(Endpoint::ChatCompletions)?;
+        let chat_completion = async {
+            let response = self
+                .send(
+                    Endpoint::ChatCompletions,
+                    self.request(Method::POST, url).json(&request),
+                )
+                .await?;
+
+            decode_json::<ChatCompletionObject>(response).await
+        };
+        let sources = async {
+            if retrieve_sources {
+                self.send_rag_retrieve(&request).await
+            } else {
+                Ok(Vec::new())
+            }
+        };
+        let (chat_completion, sources) = futures::try_join!(chat_completion, sources)?;
+
+        Ok(RagChatCompletion {
+            chat_completion,
+            sources,
+        })
+    }
+
+    /// Send a RAG chat completion request with streaming.
+    ///
+    /// If `params.retrieve_sources` is set, the sources are retrieved from `/v1/retrieve` first. The answer is then streamed from the server, which runs its own retrieval to answer. The sources are only approximate: they usually match the context of the answer, but are not guaranteed to, for example if the collection changes between the two retrievals.
+    ///
+    /// # Arguments
+    ///
+    /// * `chat_history` - The chat history including the latest user message.
+    ///
+    /// * `params` - The parameters for the retrieval and the chat completion.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the retrieved context together with the stream of chat completion chunks, or an error. The context is empty unless `params.retrieve_sources` is set. The last chunk carries the token usage of the request.
+    #[cfg(feature = "rag")]
+    pub async fn rag_chat_stream(
+        &self,
+        chat_history: &[ChatCompletionRequestMessage],
+        params: RagChatParams,
+    ) -> Result<RagChatStream, LlamaEdgeError> {
+        if chat_history.is_empty() {
+            return Err(LlamaEdgeError::InvalidArgument(
+                "chat_history cannot be empty".to_string(),
+            ));
         }
-        let mut request = builder.build();
-        request.temperature = Some(params.temperature);
-        request.top_p = Some(params.top_p);
 
-        // send the request
-        let response = reqwest::Client::new()
-            .post(url)
-            .json(&request)
-            .send()
-            .await
-            .map_err(|e| LlamaEdgeError::Operation(e.to_string()))?;
+        let retrieve_sources = params.retrieve_sources;
+        let mut request = rag_chat_request(chat_history, params)?;
 
-        // parse the response
-        let rag_context_response = response
-            .json::<Vec<RetrieveObject>>()
-            .await
-            .map_err(|e| LlamaEdgeError::Operation(e.to_string()))?;
+        // the stream fields are only meant for the chat completion
+        let sources = if retrieve_sources {
+            self.send_rag_retrieve(&request).await?
+        } else {
+            Vec::new()
+        };
 
-        Ok(rag_context_response)
+        request.stream = Some(true);
+        request.stream_options = Some(StreamOptions {
+            include_usage: Some(true),
+        });
+
+        let url = self.endpoint_url(Endpoint::ChatCompletions)?;
+        let response = self
+            .send(
+                Endpoint::ChatCompletions,
+                self.request(Method::POST, url).json(&request),
+            )
+            .await?;
+
+        let stream = response
+            .bytes_stream()
+            .map(|r| r.map_err(LlamaEdgeError::from));
+
+        Ok(RagChatStream {
+            stream: ChatCompletionStream::new(stream),
+            sources,
+        })
+    }
+
+    /// Send a retrieval request to `/v1/retrieve`.
+    #[cfg(feature = "rag")]
+    async fn send_rag_retrieve(
+        &self,
+        request: &ChatCompletionRequest,
+    ) -> Result<Vec<RetrieveObject>, LlamaEdgeError> {
+        let url = self.endpoint_url(Endpoint::Retrieve)?;
+
+        let response = self
+            .send(
+                Endpoint::Retrieve,
+                self.request(Method::POST, url).json(request),
+            )
+            .await?;
+
+        decode_json::<Vec<RetrieveObject>>(response).await
     }
 
     /// Chunk a text file into chunks.
@@ -1097,7 +1637,7 @@ impl Client {
         file_path: impl AsRef<Path>,
         chunk_capacity: usize,
     ) -> Result<ChunksResponse, LlamaEdgeError> {
-        let url = self.server_base_url.join("/v1/chunks")?;
+        let url = self.endpoint_url(Endpoint::Chunks)?;
 
         // upload the file
         let fo = self.upload_file(file_path.as_ref()).await?;
@@ -1110,19 +1650,540 @@ impl Client {
         };
 
         // send request
-        let response = reqwest::Client::new()
-            .post(url)
-            .json(&chunks_request)
-            .send()
-            .await
-            .map_err(|e| LlamaEdgeError::Operation(e.to_string()))?;
+        let response = self
+            .send(
+                Endpoint::Chunks,
+                self.request(Method::POST, url).json(&chunks_request),
+            )
+            .await?;
 
         // parse the response
-        let chunks_response = response
-            .json::<ChunksResponse>()
-            .await
-            .map_err(|e| LlamaEdgeError::Operation(e.to_string()))?;
+        let chunks_response = decode_json::<ChunksResponse>(response).await?;
 
         Ok(chunks_response)
     }
+
+    /// Ingest documents into a VectorDB collection.
+    ///
+    /// Each file is uploaded and split into chunks with [`Client::rag_chunk_file`], then the chunks are sent in batches to the embeddings endpoint, which indexes them into the collection set in `params`. Files are ingested concurrently, and a failing file does not stop the others.
+    ///
+    /// # Arguments
+    ///
+    /// * `paths` - The files and directories to ingest. Directories are searched for files with one of the extensions set in `options`.
+    ///
+    /// * `params` - The parameters for the embeddings. `vdb_server_url` and `vdb_collection_name` must be set.
+    ///
+    /// * `options` - The options for the ingestion.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the number of chunks indexed together with the ingested and failed files, or an error if the arguments are invalid or a directory cannot be read.
+    #[cfg(feature = "rag")]
+    pub async fn ingest(
+        &self,
+        paths: impl IntoIterator<Item = impl AsRef<Path>>,
+        params: EmbeddingsParams,
+        options: IngestOptions,
+    ) -> Result<IngestSummary, LlamaEdgeError> {
+        options.validate()?;
+        if params
+            .vdb_server_url
+            .as_deref()
+            .is_none_or(|url| url.trim().is_empty())
+        {
+            return Err(LlamaEdgeError::InvalidArgument(
+                "vdb_server_url must be set to ingest documents".to_string(),
+            ));
+        }
+        let collection_name = match params.vdb_collection_name.as_deref() {
+            Some(name) if !name.trim().is_empty() => name.to_string(),
+            _ => {
+                return Err(LlamaEdgeError::InvalidArgument(
+                    "vdb_collection_name must be set to ingest documents".to_string(),
+                ))
+            }
+        };
+
+        let files = options.collect_files(paths).await?;
+
+        let ingest_file = |path: std::path::PathBuf| {
+            let params = params.clone();
+            let options = &options;
+            async move {
+                let mut indexed = 0;
+                let result = async {
+                    let chunks = self
+                        .rag_chunk_file(&path, options.chunk_capacity())
+                        .await?
+                        .chunks
+                        .into_iter()
+                        .filter(|chunk| !chunk.trim().is_empty())
+                        .collect::<Vec<_>>();
+                    options.report(IngestEvent::Chunked {
+                        path: path.clone(),
+                        chunks: chunks.len(),
+                    });
+
+                    for batch in chunks.chunks(options.batch_size()) {
+                        self.embeddings(InputText::from(batch.to_vec()), params.clone())
+                            .await?;
+                        indexed += batch.len();
+                        options.report(IngestEvent::Embedded {
+                            path: path.clone(),
+                            indexed,
+                            chunks: chunks.len(),
+                        });
+                    }
+
+                    Ok::<_, LlamaEdgeError>(())
+                }
+                .await;
+
+                match &result {
+                    Ok(()) => options.report(IngestEvent::Completed {
+                        path: path.clone(),
+                        chunks: indexed,
+                    }),
+                    Err(e) => options.report(IngestEvent::Failed {
+                        path: path.clone(),
+                        error: e.to_string(),
+                    }),
+                }
+                (path, indexed, result)
+            }
+        };
+
+        let results = futures::stream::iter(files)
+            .map(ingest_file)
+            .buffered(options.concurrency())
+            .collect::<Vec<_>>()
+            .await;
+
+        let mut summary = IngestSummary {
+            collection: collection_name,
+            ..Default::default()
+        };
+        for (path, indexed, result) in results {
+            summary.chunks += indexed;
+            match result {
+                Ok(()) => summary.ingested.push(IngestedFile {
+                    path,
+                    chunks: indexed,
+                }),
+                Err(error) => summary.failed.push(IngestFailure {
+                    path,
+                    indexed,
+                    error,
+                }),
+            }
+        }
+
+        Ok(summary)
+    }
+}
+
+/// Create a plain text part of a multipart form.
+#[cfg(feature = "image")]
+fn text_part(value: String) -> Result<multipart::Part, LlamaEdgeError> {
+    multipart::Part::text(value)
+        .mime_str("text/plain")
+        .map_err(|e| LlamaEdgeError::Operation(e.to_string()))
+}
+
+/// Build the request of a RAG chat completion or retrieval.
+///
+/// # Arguments
+///
+/// * `chat_history` - The chat history.
+///
+/// * `params` - The parameters for the retrieval and the chat completion.
+#[cfg(feature = "rag")]
+fn rag_chat_request(
+    chat_history: &[ChatCompletionRequestMessage],
+    params: RagChatParams,
+) -> Result<ChatCompletionRequest, LlamaEdgeError> {
+    let mut builder = ChatCompletionRequestBuilder::new(chat_history)
+        .with_n_choices(params.n_choice)
+        .with_max_tokens(params.max_tokens)
+        .with_max_completion_tokens(params.max_completion_tokens)
+        .with_presence_penalty(params.presence_penalty)
+        .with_frequency_penalty(params.frequency_penalty)
+        .with_rag_context_window(params.context_window);
+
+    if let Some(model) = params.model {
+        builder = builder.with_model(model);
+    }
+    if let Some(stop) = params.stop {
+        builder = builder.with_stop(stop);
+    }
+    if let Some(user) = params.user {
+        builder = builder.with_user(user);
+    }
+    if let Some(response_format) = params.response_format {
+        builder = builder.with_reponse_format(response_format);
+    }
+    if let Some(tools) = params.tools {
+        builder = builder.with_tools(tools);
+    }
+    if let Some(tool_choice) = params.tool_choice {
+        builder = builder.with_tool_choice(tool_choice);
+    }
+    if let Some(vdb_config) = params.vdb_config {
+        // a malformed configuration is rejected by the server with an error that does not say what is wrong
+        vdb_config.validate()?;
+        builder = builder.with_rag_vdb_settings(
+            vdb_config.server_url,
+            vdb_config.collection_name,
+            vdb_config.limit,
+            vdb_config.score_threshold,
+            vdb_config.api_key,
+        );
+    }
+    let mut request = builder.build();
+    request.temperature = Some(params.temperature);
+    request.top_p = Some(params.top_p);
+
+    Ok(request)
+}
+
+/// Check if a header carries credentials, whose value must not be printed.
+fn is_sensitive_header(name: &str) -> bool {
+    [
+        "authorization",
+        "proxy-authorization",
+        "cookie",
+        "x-api-key",
+        "api-key",
+    ]
+    .iter()
+    .any(|sensitive| name.eq_ignore_ascii_case(sensitive))
+}
+
+/// Decode the JSON body of a response, keeping the raw body if it cannot be decoded.
+async fn decode_json<T: DeserializeOwned>(
+    response: reqwest::Response,
+) -> Result<T, LlamaEdgeError> {
+    let body = response.text().await?;
+    serde_json::from_str::<T>(&body).map_err(|e| LlamaEdgeError::Decode {
+        message: e.to_string(),
+        body,
+    })
+}
+
+/// Decode the body of a transcription or translation response according to the requested format.
+///
+/// # Arguments
+///
+/// * `response` - The response of the server.
+///
+/// * `format` - The requested format of the output.
+///
+/// * `text_of` - The function extracting the text from the JSON object of the `json` format.
+#[cfg(feature = "audio")]
+async fn decode_transcript<T: DeserializeOwned>(
+    response: reqwest::Response,
+    format: TranscriptionFormat,
+    text_of: impl FnOnce(T) -> String,
+) -> Result<Transcript, LlamaEdgeError> {
+    match format {
+        TranscriptionFormat::Json => {
+            Ok(Transcript::Text(text_of(decode_json::<T>(response).await?)))
+        }
+        TranscriptionFormat::VerboseJson => Ok(Transcript::Verbose(
+            decode_json::<VerboseTranscript>(response).await?,
+        )),
+        TranscriptionFormat::Text => Ok(Transcript::Text(response.text().await?)),
+        TranscriptionFormat::Srt => Ok(Transcript::Srt(response.text().await?)),
+        TranscriptionFormat::Vtt => Ok(Transcript::Vtt(response.text().await?)),
+    }
+}
+
+impl fmt::Debug for Client {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Client")
+            .field("server_base_url", &self.server_base_url)
+            .field("api_version", &self.api_version)
+            .field("http_client", &self.http_client)
+            .field("auth_provider", &self.auth_provider.is_some())
+            .field("retry_policy", &self.retry_policy)
+            .field("endpoint_retry_policies", &self.endpoint_retry_policies)
+            .finish()
+    }
+}
+
+/// Builder for creating a [`Client`].
+pub struct ClientBuilder {
+    server_base_url: String,
+    api_version: String,
+    connect_timeout: Option<Duration>,
+    read_timeout: Option<Duration>,
+    timeout: Option<Duration>,
+    user_agent: String,
+    proxies: Vec<reqwest::Proxy>,
+    no_proxy: bool,
+    default_headers: Vec<(String, String)>,
+    http_client: Option<reqwest::Client>,
+    auth_provider: Option<Arc<dyn AuthProvider>>,
+    retry_policy: RetryPolicy,
+    endpoint_retry_policies: HashMap<Endpoint, RetryPolicy>,
+}
+impl ClientBuilder {
+    /// Create a new builder.
+    ///
+    /// # Arguments
+    ///
+    /// * `server_base_url` - The base URL of the LlamaEdge API server.
+    pub fn new(server_base_url: impl AsRef<str>) -> Self {
+        Self {
+            server_base_url: server_base_url.as_ref().to_string(),
+            api_version: DEFAULT_API_VERSION.to_string(),
+            connect_timeout: None,
+            read_timeout: None,
+            timeout: None,
+            user_agent: DEFAULT_USER_AGENT.to_string(),
+            proxies: Vec::new(),
+            no_proxy: false,
+            default_headers: Vec::new(),
+            http_client: None,
+            auth_provider: None,
+            retry_policy: RetryPolicy::none(),
+            endpoint_retry_policies: HashMap::new(),
+        }
+    }
+
+    /// Set the API version segment prepended to the path of every endpoint. Defaults to `v1`. An empty string omits the segment.
+    pub fn with_api_version(mut self, api_version: impl Into<String>) -> Self {
+        self.api_version = api_version.into();
+        self
+    }
+
+    /// Set the timeout for establishing a connection to the server.
+    pub fn with_connect_timeout(mut self, timeout: Duration) -> Self {
+        self.connect_timeout = Some(timeout);
+        self
+    }
+
+    /// Set the timeout for each read operation on a connection. The timeout is reset after each successful read, so it also applies to long-running streaming responses.
+    pub fn with_read_timeout(mut self, timeout: Duration) -> Self {
+        self.read_timeout = Some(timeout);
+        self
+    }
+
+    /// Set the total timeout of a request, from connecting until the response body has been fully received.
+    ///
+    /// Note that this timeout also bounds streaming chat completions. Use `with_read_timeout` to limit stalled connections without limiting the length of a stream.
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Set the `User-Agent` header sent with every request. Defaults to `llamaedge-rust/<version>`.
+    pub fn with_user_agent(mut self, user_agent: impl Into<String>) -> Self {
+        self.user_agent = user_agent.into();
+        self
+    }
+
+    /// Add a proxy to route the requests through. By default, the system proxy settings are used.
+    pub fn with_proxy(mut self, proxy: reqwest::Proxy) -> Self {
+        self.proxies.push(proxy);
+        self
+    }
+
+    /// Disable all proxies, including the system proxy settings.
+    pub fn without_proxy(mut self) -> Self {
+        self.no_proxy = true;
+        self
+    }
+
+    /// Add a header sent with every request.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of the header.
+    ///
+    /// * `value` - The value of the header.
+    pub fn with_default_header(
+        mut self,
+        name: impl Into<String>,
+        value: impl Into<String>,
+    ) -> Self {
+        self.default_headers.push((name.into(), value.into()));
+        self
+    }
+
+    /// Use a pre-configured `reqwest::Client` to send the requests.
+    ///
+    /// The timeouts, user agent, proxies and default headers set on this builder are ignored, since they are part of the configuration of the given client.
+    pub fn with_http_client(mut self, http_client: reqwest::Client) -> Self {
+        self.http_client = Some(http_client);
+        self
+    }
+
+    /// Set the API key sent as a bearer token in the `Authorization` header of every request.
+    ///
+    /// If neither an API key nor an auth provider is set, the key is read from the `LLAMAEDGE_API_KEY` environment variable, if present.
+    pub fn with_api_key(mut self, api_key: impl Into<String>) -> Self {
+        self.auth_provider = Some(Arc::new(ApiKey::new(api_key)));
+        self
+    }
+
+    /// Set the provider of the bearer token sent in the `Authorization` header of every request. The provider is queried before each request, so the token can be rotated at runtime.
+    pub fn with_auth_provider(mut self, auth_provider: impl AuthProvider + 'static) -> Self {
+        self.auth_provider = Some(Arc::new(auth_provider));
+        self
+    }
+
+    /// Set the retry policy for requests failing with a transient error. By default, requests are not retried.
+    ///
+    /// The policy applies to the endpoints that can be called again safely, see [`Endpoint::is_idempotent`], and to `GET` and `DELETE` requests to any endpoint. Use `with_endpoint_retry_policy` to override it for a single endpoint.
+    pub fn with_retry_policy(mut self, retry_policy: RetryPolicy) -> Self {
+        self.retry_policy = retry_policy;
+        self
+    }
+
+    /// Set the retry policy for a single endpoint, overriding the policy set with `with_retry_policy`.
+    ///
+    /// # Arguments
+    ///
+    /// * `endpoint` - The endpoint the policy applies to.
+    ///
+    /// * `retry_policy` - The retry policy.
+    pub fn with_endpoint_retry_policy(
+        mut self,
+        endpoint: Endpoint,
+        retry_policy: RetryPolicy,
+    ) -> Self {
+        self.endpoint_retry_policies.insert(endpoint, retry_policy);
+        self
+    }
+
+    /// Build a blocking client with the configured settings.
+    ///
+    /// The blocking client has its own connection pool, which is not shared with any async client built from the same settings. A client set with `with_http_client` is used as is, so it must not also be used from another runtime.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the blocking client or an error.
+    #[cfg(feature = "blocking")]
+    pub fn build_blocking(self) -> Result<blocking::Client, LlamaEdgeError> {
+        blocking::Client::from_builder(self)
+    }
+
+    /// Build the client.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the client or an error.
+    pub fn build(self) -> Result<Client, LlamaEdgeError> {
+        // a trailing slash marks the path of the base URL as a directory, so that the endpoint paths are appended to it
+        let url_str = format!("{}/", self.server_base_url.trim_end_matches('/'));
+        let server_base_url = Url::parse(&url_str).map_err(LlamaEdgeError::UrlParse)?;
+        if server_base_url.cannot_be_a_base() {
+            return Err(LlamaEdgeError::InvalidArgument(format!(
+                "The server base URL cannot be a base: {}",
+                self.server_base_url
+            )));
+        }
+        if server_base_url.query().is_some() || server_base_url.fragment().is_some() {
+            return Err(LlamaEdgeError::InvalidArgument(format!(
+                "The server base URL cannot have a query or fragment: {}",
+                self.server_base_url
+            )));
+        }
+
+        let api_version = self.api_version.trim_matches('/').to_string();
+
+        let http_client = match self.http_client {
+            Some(http_client) => http_client,
+            None => {
+                let mut headers = HeaderMap::new();
+                for (name, value) in self.default_headers.iter() {
+                    let header_name = HeaderName::from_bytes(name.as_bytes()).map_err(|e| {
+                        LlamaEdgeError::InvalidArgument(format!(
+                            "Invalid header name `{}`: {}",
+                            name, e
+                        ))
+                    })?;
+                    let mut header_value = HeaderValue::from_str(value).map_err(|e| {
+                        LlamaEdgeError::InvalidArgument(format!(
+                            "Invalid value for header `{}`: {}",
+                            name, e
+                        ))
+                    })?;
+                    header_value.set_sensitive(is_sensitive_header(name));
+                    headers.append(header_name, header_value);
+                }
+
+                let mut builder = reqwest::Client::builder()
+                    .user_agent(self.user_agent)
+                    .default_headers(headers);
+                if let Some(timeout) = self.connect_timeout {
+                    builder = builder.connect_timeout(timeout);
+                }
+                if let Some(timeout) = self.read_timeout {
+                    builder = builder.read_timeout(timeout);
+                }
+                if let Some(timeout) = self.timeout {
+                    builder = builder.timeout(timeout);
+                }
+                if self.no_proxy {
+                    builder = builder.no_proxy();
+                }
+                for proxy in self.proxies {
+                    builder = builder.proxy(proxy);
+                }
+
+                builder
+                    .build()
+                    .map_err(|e| LlamaEdgeError::Operation(e.to_string()))?
+            }
+        };
+
+        let auth_provider = match self.auth_provider {
+            Some(auth_provider) => Some(auth_provider),
+            None => ApiKey::from_env().map(|api_key| Arc::new(api_key) as Arc<dyn AuthProvider>),
+        };
+
+        Ok(Client {
+            server_base_url,
+            api_version,
+            http_client,
+            auth_provider,
+            retry_policy: self.retry_policy,
+            endpoint_retry_policies: self.endpoint_retry_policies,
+        })
+    }
+}
+impl fmt::Debug for ClientBuilder {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ClientBuilder")
+            .field("server_base_url", &self.server_base_url)
+            .field("api_version", &self.api_version)
+            .field("connect_timeout", &self.connect_timeout)
+            .field("read_timeout", &self.read_timeout)
+            .field("timeout", &self.timeout)
+            .field("user_agent", &self.user_agent)
+            .field("proxies", &self.proxies)
+            .field("no_proxy", &self.no_proxy)
+            .field(
+                "default_headers",
+                &self
+                    .default_headers
+                    .iter()
+                    .map(|(name, value)| {
+                        let value = if is_sensitive_header(name) {
+                            "***"
+                        } else {
+                            value.as_str()
+                        };
+                        (name.as_str(), value)
+                    })
+                    .collect::<Vec<_>>(),
+            )
+            .field("http_client", &self.http_client)
+            .field("auth_provider", &self.auth_provider.is_some())
+            .field("retry_policy", &self.retry_policy)
+            .field("endpoint_retry_policies", &self.endpoint_retry_policies)
+            .finish()
+    }
 }
@@ -1,15 +1,16 @@
 //! Parameters for the chat completion API.
 
+#[cfg(feature = "audio")]
+use crate::audio::TranscriptionFormat;
+#[cfg(feature = "rag")]
+use crate::error::LlamaEdgeError;
+#[cfg(feature = "image")]
+use crate::image::ImageInput;
 #[cfg(feature = "audio")]
 use endpoints::audio::transcription::TimestampGranularity;
 use endpoints::chat::{ChatResponseFormat, Tool, ToolChoice};
 #[cfg(feature = "image")]
-use endpoints::{
-    files::FileObject,
-    images::{SamplingMethod, Scheduler},
-};
-#[cfg(feature = "image")]
-use std::path::PathBuf;
+use endpoints::images::{SamplingMethod, Scheduler};
 
 #[cfg(feature = "image")]
 pub type ImageResponseFormat = endpoints::images::ResponseFormat;
@@ -132,6 +133,8 @@ pub struct RagChatParams {
     pub context_window: u64,
     /// The configuration for the VectorDB server.
     pub vdb_config: Option<RagVdbConfig>,
+    /// Whether `rag_chat` and `rag_chat_stream` also return the sources of the answer. The server does not return the context it answers with, so the sources are fetched with a second retrieval from `/v1/retrieve`, which doubles the load on the VectorDB server. Defaults to `false`.
+    pub retrieve_sources: bool,
 }
 #[cfg(feature = "rag")]
 impl Default for RagChatParams {
@@ -152,6 +155,7 @@ impl Default for RagChatParams {
             tool_choice: None,
             context_window: 1,
             vdb_config: None,
+            retrieve_sources: false,
         }
     }
 }
@@ -164,13 +168,188 @@ pub struct RagVdbConfig {
     pub server_url: String,
     /// The names of the collections in VectorDB.
     pub collection_name: Vec<String>,
-    /// Max number of retrieved results. The number of the values must be the same as the number of `collection_name`.
+    /// Max number of retrieved results. The number of the values must be the same as the number of `collection_name`, which [`RagVdbConfig::builder`] guarantees.
     pub limit: Vec<u64>,
-    /// The score threshold for the retrieved results. The number of the values must be the same as the number of `collection_name`.
+    /// The score threshold for the retrieved results, between 0 and 1. The number of the values must be the same as the number of `collection_name`, which [`RagVdbConfig::builder`] guarantees.
     pub score_threshold: Vec<f32>,
     /// The API key for the VectorDB server.
     pub api_key: Option<String>,
 }
+#[cfg(feature = "rag")]
+impl RagVdbConfig {
+    /// Create a builder for the configuration, which keeps the settings of each collection together.
+    ///
+    /// # Arguments
+    ///
+    /// * `server_url` - The URL of the VectorDB server.
+    ///
+    /// # Returns
+    ///
+    /// A `RagVdbConfigBuilder` instance.
+    pub fn builder(server_url: impl Into<String>) -> RagVdbConfigBuilder {
+        RagVdbConfigBuilder::new(server_url)
+    }
+
+    /// Check that the configuration can be sent to the server: the server URL must be a valid HTTP URL, and every collection must have a non-empty name, a non-zero limit and a score threshold between 0 and 1.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing nothing, or an `InvalidArgument` error describing the first invalid setting.
+    pub fn validate(&self) -> Result<(), LlamaEdgeError> {
+        let url = url::Url::parse(&self.server_url).map_err(|e| {
+            LlamaEdgeError::InvalidArgument(format!(
+                "Invalid VectorDB server URL `{}`: {}",
+                self.server_url, e
+            ))
+        })?;
+        if !matches!(url.scheme(), "http" | "https") {
+            return Err(LlamaEdgeError::InvalidArgument(format!(
+                "The VectorDB server URL must use http or https: {}",
+                self.server_url
+            )));
+        }
+
+        if self.collection_name.is_empty() {
+            return Err(LlamaEdgeError::InvalidArgument(
+                "At least one VectorDB collection is required".to_string(),
+            ));
+        }
+        if self.limit.len() != self.collection_name.len() {
+            return Err(LlamaEdgeError::InvalidArgument(format!(
+                "Expected {} limits, one per collection, but got {}",
+                self.collection_name.len(),
+                self.limit.len()
+            )));
+        }
+        if self.score_threshold.len() != self.collection_name.len() {
+            return Err(LlamaEdgeError::InvalidArgument(format!(
+                "Expected {} score thresholds, one per collection, but got {}",
+                self.collection_name.len(),
+                self.score_threshold.len()
+            )));
+        }
+
+        for ((name, limit), score_threshold) in self
+            .collection_name
+            .iter()
+            .zip(&self.limit)
+            .zip(&self.score_threshold)
+        {
+            if name.trim().is_empty() {
+                return Err(LlamaEdgeError::InvalidArgument(
+                    "The name of a VectorDB collection cannot be empty".to_string(),
+                ));
+            }
+            if *limit == 0 {
+                return Err(LlamaEdgeError::InvalidArgument(format!(
+                    "The limit of the collection `{}` cannot be zero",
+                    name
+                )));
+            }
+            // NaN fails the range check
+            if !(0.0..=1.0).contains(score_threshold) {
+                return Err(LlamaEdgeError::InvalidArgument(format!(
+                    "The score threshold of the collection `{}` must be between 0 and 1, but got {}",
+                    name, score_threshold
+                )));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Builder for [`RagVdbConfig`], taking the limit and score threshold of each collection together with its name, so they cannot get out of step.
+///
+/// # Example
+///
+/// ```rust
+/// use llamaedge::params::RagVdbConfig;
+///
+/// let vdb_config = RagVdbConfig::builder("http://localhost:6333")
+///     .with_collection("paris", 5, 0.5)
+///     .with_collection("london", 3, 0.7)
+///     .build()
+///     .unwrap();
+///
+/// assert_eq!(vdb_config.collection_name, vec!["paris", "london"]);
+/// assert_eq!(vdb_config.limit, vec![5, 3]);
+/// ```
+#[cfg(feature = "rag")]
+#[derive(Debug, Clone)]
+pub struct RagVdbConfigBuilder {
+    server_url: String,
+    collections: Vec<(String, u64, f32)>,
+    api_key: Option<String>,
+}
+#[cfg(feature = "rag")]
+impl RagVdbConfigBuilder {
+    /// Create a new builder without any collection.
+    ///
+    /// # Arguments
+    ///
+    /// * `server_url` - The URL of the VectorDB server.
+    ///
+    /// # Returns
+    ///
+    /// A `RagVdbConfigBuilder` instance.
+    pub fn new(server_url: impl Into<String>) -> Self {
+        Self {
+            server_url: server_url.into(),
+            collections: Vec::new(),
+            api_key: None,
+        }
+    }
+
+    /// Add a collection to retrieve the context from.
+    ///
+    /// # Arguments
+    ///
+    /// * `collection_name` - The name of the collection.
+    ///
+    /// * `limit` - The maximum number of results retrieved from the collection. Must be non-zero.
+    ///
+    /// * `score_threshold` - The minimum score of the results retrieved from the collection, between 0 and 1.
+    pub fn with_collection(
+        mut self,
+        collection_name: impl Into<String>,
+        limit: u64,
+        score_threshold: f32,
+    ) -> Self {
+        self.collections
+            .push((collection_name.into(), limit, score_threshold));
+        self
+    }
+
+    /// Set the API key for the VectorDB server.
+    pub fn with_api_key(mut self, api_key: impl Into<String>) -> Self {
+        self.api_key = Some(api_key.into());
+        self
+    }
+
+    /// Build the configuration.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the configuration, or an `InvalidArgument` error if it is invalid as described in [`RagVdbConfig::validate`].
+    pub fn build(self) -> Result<RagVdbConfig, LlamaEdgeError> {
+        let mut vdb_config = RagVdbConfig {
+            server_url: self.server_url,
+            collection_name: Vec::with_capacity(self.collections.len()),
+            limit: Vec::with_capacity(self.collections.len()),
+            score_threshold: Vec::with_capacity(self.collections.len()),
+            api_key: self.api_key,
+        };
+        for (collection_name, limit, score_threshold) in self.collections {
+            vdb_config.collection_name.push(collection_name);
+            vdb_config.limit.push(limit);
+            vdb_config.score_threshold.push(score_threshold);
+        }
+        vdb_config.validate()?;
+
+        Ok(vdb_config)
+    }
+}
 
 /// Parameters for the transcription API.
 #[cfg(feature = "audio")]
@@ -180,8 +359,8 @@ pub struct TranscriptionParams {
     pub model: Option<String>,
     /// An optional text to guide the model's style or continue a previous audio segment. The prompt should match the audio language.
     pub prompt: Option<String>,
-    /// The format of the transcript output, in one of these options: `json`, `text`, `srt`, `verbose_json`, or `vtt`.
-    pub response_format: String,
+    /// The format of the transcript output. Defaults to `TranscriptionFormat::Json`.
+    pub response_format: TranscriptionFormat,
     /// The sampling temperature, between 0 and 1. Higher values like 0.8 will make the output more random, while lower values like 0.2 will make it more focused and deterministic. If set to 0, the model will use [log probability](https://en.wikipedia.org/wiki/Log_probability) to automatically increase the temperature until certain thresholds are hit. Defaults to 0.0.
     pub temperature: f64,
     /// The timestamp granularities to populate for this transcription.
@@ -202,6 +381,8 @@ pub struct TranscriptionParams {
     pub split_on_word: bool,
     /// Use the new computation context. Defaults to false.
     pub use_new_context: bool,
+    /// Convert a WAV input to 16 kHz mono 16-bit PCM, the format expected by whisper backends, before uploading it. Other inputs are uploaded unchanged. Defaults to false.
+    pub preprocess_audio: bool,
 }
 #[cfg(feature = "audio")]
 impl Default for TranscriptionParams {
@@ -209,7 +390,7 @@ impl Default for TranscriptionParams {
         Self {
             model: None,
             prompt: None,
-            response_format: "json".to_string(),
+            response_format: TranscriptionFormat::Json,
             temperature: 0.0,
             timestamp_granularities: Some(vec![TimestampGranularity::Segment]),
             detect_language: false,
@@ -219,6 +400,7 @@ impl Default for TranscriptionParams {
             max_len: 0,
             split_on_word: false,
             use_new_context: false,
+            preprocess_audio: false,
         }
     }
 }
@@ -231,8 +413,8 @@ pub struct TranslationParams {
     pub model: Option<String>,
     /// An optional text to guide the model's style or continue a previous audio segment. The prompt should be in English.
     pub prompt: Option<String>,
-    /// The format of the transcript output, in one of these options: `json`, `text`, `srt`, `verbose_json`, or `vtt`. Defaults to `json`.
-    pub response_format: String,
+    /// The format of the translation output. Defaults to `TranscriptionFormat::Json`.
+    pub response_format: TranscriptionFormat,
     /// The sampling temperature, between 0 and 1. Higher values like 0.8 will make the output more random, while lower values like 0.2 will make it more focused and deterministic. If set to 0, the model will use [log probability](https://en.wikipedia.org/wiki/Log_probability) to automatically increase the temperature until certain thresholds are hit. Defaults to 0.0.
     pub temperature: f64,
     /// automatically detect the spoken language in the provided audio input. Defaults to false.
@@ -249,6 +431,8 @@ pub struct TranslationParams {
     pub split_on_word: bool,
     /// Use the new computation context. Defaults to false.
     pub use_new_context: bool,
+    /// Convert a WAV input to 16 kHz mono 16-bit PCM, the format expected by whisper backends, before uploading it. Other inputs are uploaded unchanged. Defaults to false.
+    pub preprocess_audio: bool,
 }
 #[cfg(feature = "audio")]
 impl Default for TranslationParams {
@@ -256,7 +440,7 @@ impl Default for TranslationParams {
         Self {
             model: None,
             prompt: None,
-            response_format: "json".to_string(),
+            response_format: TranscriptionFormat::Json,
             temperature: 0.0,
             detect_language: false,
             offset_time: 0,
@@ -265,6 +449,7 @@ impl Default for TranslationParams {
             max_len: 0,
             split_on_word: false,
             use_new_context: false,
+            preprocess_audio: false,
         }
     }
 }
@@ -331,8 +516,8 @@ pub struct ImageCreateParams {
     pub width: usize,
     /// Strength to apply Control Net. Defaults to `0.9`.
     pub control_strength: f32,
-    /// The image to control the generation.
-    pub control_image: Option<FileObject>,
+    /// The image to control the generation. A local image is uploaded to the server before the generation request is sent.
+    pub control_image: Option<ImageInput>,
     /// RNG seed. Negative value means to use random seed. Defaults to `42`.
     pub seed: i32,
     /// Strength for noising/unnoising. Defaults to `0.75`.
@@ -375,8 +560,8 @@ impl Default for ImageCreateParams {
 pub struct ImageEditParams {
     /// Negative prompt for the image generation.
     pub negative_prompt: Option<String>,
-    /// An additional image whose fully transparent areas (e.g. where alpha is zero) indicate where `image` should be edited. Must have the same dimensions as the input image.
-    pub mask: Option<PathBuf>,
+    /// An additional image whose fully transparent areas (e.g. where alpha is zero) indicate where `image` should be edited. Must have the same dimensions as the input image, which is checked before the request is sent.
+    pub mask: Option<ImageInput>,
     /// The model to use for image generation.
     pub model: String,
     /// The number of images to generate. Defaults to `1`.
@@ -398,7 +583,7 @@ pub struct ImageEditParams {
     /// strength to apply Control Net. Defaults to `0.9`.
     pub control_strength: f32,
     /// The image to control the generation.
-    pub control_image: Option<PathBuf>,
+    pub control_image: Option<ImageInput>,
     /// RNG seed. Negative value means to use random seed. Defaults to `42`.
     pub seed: i32,
     /// Strength for noising/unnoising. Defaults to `0.75`.
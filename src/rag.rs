@@ -0,0 +1,36 @@
+//! Types for the RAG (retrieval-augmented generation) APIs.
+
+pub mod chunk;
+pub mod ingest;
+pub mod vdb;
+
+use crate::stream::ChatCompletionStream;
+use endpoints::{chat::ChatCompletionObject, rag::RetrieveObject};
+
+/// The outcome of [`Client::rag_chat`](crate::Client::rag_chat).
+#[derive(Debug)]
+pub struct RagChatCompletion {
+    /// The chat completion carrying the answer.
+    pub chat_completion: ChatCompletionObject,
+    /// The context retrieved from the VectorDB server for the last user messages, by a separate request to `/v1/retrieve`. It approximates the context the answer is based on, but is not guaranteed to match it. Empty unless `retrieve_sources` is set in the parameters.
+    pub sources: Vec<RetrieveObject>,
+}
+impl RagChatCompletion {
+    /// Get the content of the first choice, or an empty string if the model produced none.
+    pub fn answer(&self) -> &str {
+        self.chat_completion
+            .choices
+            .first()
+            .and_then(|choice| choice.message.content.as_deref())
+            .unwrap_or_default()
+    }
+}
+
+/// The outcome of [`Client::rag_chat_stream`](crate::Client::rag_chat_stream): the retrieved context, and the stream of the answer.
+#[derive(Debug)]
+pub struct RagChatStream<S = ChatCompletionStream> {
+    /// The stream of chat completion chunks carrying the answer.
+    pub stream: S,
+    /// The context retrieved from the VectorDB server for the last user messages, by a separate request to `/v1/retrieve`. It approximates the context the answer is based on, but is not guaranteed to match it. Empty unless `retrieve_sources` is set in the parameters.
+    pub sources: Vec<RetrieveObject>,
+}
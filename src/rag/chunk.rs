@@ -0,0 +1,430 @@
+//! Local chunking of documents, without uploading them to the server.
+//!
+//! Unlike [`Client::rag_chunk_file`](crate::Client::rag_chunk_file), the chunks are computed locally, so any text can be chunked offline, inspected, and sent to [`Client::embeddings`](crate::Client::embeddings) as is. Each chunk records its byte offsets in the source text.
+//!
+//! # Example
+//!
+//! ```rust
+//! use llamaedge::rag::chunk::{split_text, ChunkOptions, Splitter};
+//!
+//! let text = "Paris is the capital of France. Berlin is the capital of Germany.";
+//! let options = ChunkOptions::new()
+//!     .with_splitter(Splitter::Sentences)
+//!     .with_capacity(40);
+//!
+//! let chunks = split_text(text, &options).unwrap();
+//! assert_eq!(chunks.len(), 2);
+//! assert_eq!(chunks[1].text, "Berlin is the capital of Germany.");
+//! assert_eq!(&text[chunks[1].start..chunks[1].end], chunks[1].text);
+//! ```
+
+use crate::error::LlamaEdgeError;
+use endpoints::embeddings::ChunksResponse;
+use std::{ops::Range, path::Path};
+
+/// The number of characters of a word counted as one token by [`approximate_tokens`].
+const CHARS_PER_TOKEN: usize = 4;
+
+/// The strategy used to split a text into chunks.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Splitter {
+    /// Split anywhere, into chunks of at most `capacity` characters.
+    #[default]
+    Characters,
+    /// Split between words, into chunks of at most `capacity` tokens as estimated by [`approximate_tokens`].
+    Tokens,
+    /// Split between sentences, into chunks of at most `capacity` characters. Sentences longer than `capacity` are split between words.
+    Sentences,
+    /// Split a Markdown document between sentences like [`Splitter::Sentences`], but never across a heading, so each chunk belongs to a single section.
+    Markdown,
+}
+
+/// Options for splitting a text into chunks.
+#[derive(Debug, Clone)]
+pub struct ChunkOptions {
+    splitter: Splitter,
+    capacity: usize,
+    overlap: usize,
+}
+impl ChunkOptions {
+    /// Create options with the default settings: chunks of at most 1024 characters, without overlap.
+    pub fn new() -> Self {
+        Self {
+            splitter: Splitter::default(),
+            capacity: 1024,
+            overlap: 0,
+        }
+    }
+
+    /// Set the strategy used to split the text.
+    pub fn with_splitter(mut self, splitter: Splitter) -> Self {
+        self.splitter = splitter;
+        self
+    }
+
+    /// Set the maximum size of a chunk, in tokens for [`Splitter::Tokens`] and in characters otherwise.
+    pub fn with_capacity(mut self, capacity: usize) -> Self {
+        self.capacity = capacity;
+        self
+    }
+
+    /// Set the maximum size of the end of a chunk repeated at the start of the next one, in the unit of the capacity. Only whole words or sentences are repeated, depending on the splitter.
+    pub fn with_overlap(mut self, overlap: usize) -> Self {
+        self.overlap = overlap;
+        self
+    }
+
+    /// Get the strategy used to split the text.
+    pub fn splitter(&self) -> Splitter {
+        self.splitter
+    }
+
+    /// Get the maximum size of a chunk.
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Get the maximum size of the overlap between consecutive chunks.
+    pub fn overlap(&self) -> usize {
+        self.overlap
+    }
+
+    /// Check that the options are consistent.
+    pub(crate) fn validate(&self) -> Result<(), LlamaEdgeError> {
+        if self.capacity == 0 {
+            return Err(LlamaEdgeError::InvalidArgument(
+                "The chunk capacity cannot be zero".to_string(),
+            ));
+        }
+        if self.overlap >= self.capacity {
+            return Err(LlamaEdgeError::InvalidArgument(format!(
+                "The overlap ({}) must be smaller than the chunk capacity ({})",
+                self.overlap, self.capacity
+            )));
+        }
+
+        Ok(())
+    }
+
+    /// Measure a piece of text in the unit of the capacity.
+    fn measure(&self, text: &str) -> usize {
+        match self.splitter {
+            Splitter::Tokens => approximate_tokens(text),
+            _ => text.chars().count(),
+        }
+    }
+}
+impl Default for ChunkOptions {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// A chunk of a text.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TextChunk {
+    /// The content of the chunk, without leading and trailing whitespace.
+    pub text: String,
+    /// The byte offset of the start of the chunk in the source text.
+    pub start: usize,
+    /// The byte offset of the end of the chunk in the source text.
+    pub end: usize,
+}
+
+/// The chunks of a file, as returned by [`chunk_file`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DocumentChunks {
+    /// The name of the file.
+    pub filename: String,
+    /// The chunks of the file.
+    pub chunks: Vec<TextChunk>,
+}
+impl DocumentChunks {
+    /// Get the content of the chunks, ready to be sent to [`Client::embeddings`](crate::Client::embeddings).
+    pub fn texts(&self) -> Vec<String> {
+        self.chunks.iter().map(|chunk| chunk.text.clone()).collect()
+    }
+}
+impl From<DocumentChunks> for ChunksResponse {
+    /// Convert the chunks into the response of [`Client::rag_chunk_file`](crate::Client::rag_chunk_file). The `id` is empty, since the file was not uploaded.
+    fn from(chunks: DocumentChunks) -> Self {
+        ChunksResponse {
+            id: String::new(),
+            filename: chunks.filename,
+            chunks: chunks.chunks.into_iter().map(|chunk| chunk.text).collect(),
+        }
+    }
+}
+
+/// Estimate the number of tokens of a text, counting one token for every four characters of each word, rounded up.
+///
+/// This is a rule of thumb for English text: the actual count depends on the tokenizer of the model.
+pub fn approximate_tokens(text: &str) -> usize {
+    text.split_whitespace()
+        .map(|word| word.chars().count().div_ceil(CHARS_PER_TOKEN))
+        .sum()
+}
+
+/// Split a text into chunks.
+///
+/// # Arguments
+///
+/// * `text` - The text to split.
+///
+/// * `options` - The options for splitting the text.
+///
+/// # Returns
+///
+/// A `Result` containing the chunks in the order of the text, or an error if the options are invalid.
+pub fn split_text(text: &str, options: &ChunkOptions) -> Result<Vec<TextChunk>, LlamaEdgeError> {
+    options.validate()?;
+
+    let mut chunks = Vec::new();
+    match options.splitter {
+        Splitter::Markdown => {
+            for section in markdown_sections(text) {
+                pack(text, section, options, &mut chunks);
+            }
+        }
+        _ => pack(text, 0..text.len(), options, &mut chunks),
+    }
+
+    Ok(chunks)
+}
+
+/// Split a text file into chunks.
+///
+/// # Arguments
+///
+/// * `file_path` - The path to the file to split. The file must be encoded in UTF-8.
+///
+/// * `options` - The options for splitting the file.
+///
+/// # Returns
+///
+/// A `Result` containing the chunks of the file, or an error if the options are invalid or the file cannot be read.
+pub fn chunk_file(
+    file_path: impl AsRef<Path>,
+    options: &ChunkOptions,
+) -> Result<DocumentChunks, LlamaEdgeError> {
+    let file_path = file_path.as_ref();
+    let text = std::fs::read_to_string(file_path).map_err(|e| {
+        LlamaEdgeError::Operation(format!(
+            "Failed to read the file {}: {}",
+            file_path.display(),
+            e
+        ))
+    })?;
+    let filename = file_path
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default();
+
+    Ok(DocumentChunks {
+        filename,
+        chunks: split_text(&text, options)?,
+    })
+}
+
+/// The granularity of the segments a chunk is made of, from the coarsest to the finest.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Level {
+    Sentences,
+    Words,
+    Characters,
+}
+
+/// Pack the segments of a range of the text into chunks of at most `capacity`, each starting with the overlap of the previous one.
+fn pack(text: &str, range: Range<usize>, options: &ChunkOptions, chunks: &mut Vec<TextChunk>) {
+    let level = match options.splitter {
+        Splitter::Characters => Level::Characters,
+        Splitter::Tokens => Level::Words,
+        Splitter::Sentences | Splitter::Markdown => Level::Sentences,
+    };
+    let mut segments = Vec::new();
+    segment(text, range, level, options, &mut segments);
+
+    // sizes[i] is the size of the first i segments
+    let mut sizes = vec![0];
+    for segment in &segments {
+        sizes.push(sizes[sizes.len() - 1] + options.measure(&text[segment.clone()]));
+    }
+    let size = |from: usize, to: usize| sizes[to] - sizes[from];
+
+    let mut first = 0;
+    while first < segments.len() {
+        let mut end = first + 1;
+        while end < segments.len() && size(first, end + 1) <= options.capacity {
+            end += 1;
+        }
+        push_chunk(text, segments[first].start..segments[end - 1].end, chunks);
+        if end == segments.len() {
+            break;
+        }
+
+        // repeat the last segments that fit in the overlap, leaving room for the next segment
+        let mut next = end;
+        while next > first + 1
+            && size(next - 1, end) <= options.overlap
+            && size(next - 1, end + 1) <= options.capacity
+        {
+            next -= 1;
+        }
+        first = next;
+    }
+}
+
+/// Split a range of the text into contiguous segments that each fit in the capacity, refining the segments too large for it.
+fn segment(
+    text: &str,
+    range: Range<usize>,
+    level: Level,
+    options: &ChunkOptions,
+    segments: &mut Vec<Range<usize>>,
+) {
+    let pieces = match level {
+        Level::Sentences => sentences(text, range),
+        Level::Words => words(text, range),
+        Level::Characters => {
+            // a token is counted for every few characters, so cut words into pieces of one token
+            let width = match options.splitter {
+                Splitter::Tokens => CHARS_PER_TOKEN,
+                _ => 1,
+            };
+            segments.extend(characters(text, range, width));
+            return;
+        }
+    };
+
+    for piece in pieces {
+        if options.measure(&text[piece.clone()]) <= options.capacity {
+            segments.push(piece);
+        } else {
+            let finer = match level {
+                Level::Sentences => Level::Words,
+                _ => Level::Characters,
+            };
+            segment(text, piece, finer, options, segments);
+        }
+    }
+}
+
+/// Split a range of the text at the given boundaries, which must be increasing and within the range.
+fn split_at(range: Range<usize>, boundaries: impl IntoIterator<Item = usize>) -> Vec<Range<usize>> {
+    let mut pieces = Vec::new();
+    let mut start = range.start;
+    for boundary in boundaries {
+        if boundary > start {
+            pieces.push(start..boundary);
+            start = boundary;
+        }
+    }
+    if start < range.end || pieces.is_empty() {
+        pieces.push(start..range.end);
+    }
+    pieces
+}
+
+/// Split a range of the text into sentences, each followed by its trailing whitespace.
+///
+/// A sentence ends with `.`, `!` or `?`, possibly followed by closing quotes or brackets, then whitespace, or with a blank line.
+fn sentences(text: &str, range: Range<usize>) -> Vec<Range<usize>> {
+    let mut boundaries = Vec::new();
+    let mut ended = false;
+    let mut newlines = 0;
+    let mut gap = false;
+    for (offset, c) in text[range.clone()].char_indices() {
+        if c.is_whitespace() {
+            if c == '\n' {
+                newlines += 1;
+            }
+            gap |= ended || newlines >= 2;
+            continue;
+        }
+
+        if gap {
+            boundaries.push(range.start + offset);
+        }
+        gap = false;
+        newlines = 0;
+        ended = match c {
+            '.' | '!' | '?' | '。' | '！' | '？' => true,
+            '"' | '\'' | ')' | ']' | '”' | '’' => ended,
+            _ => false,
+        };
+    }
+
+    split_at(range, boundaries)
+}
+
+/// Split a range of the text into words, each followed by its trailing whitespace.
+fn words(text: &str, range: Range<usize>) -> Vec<Range<usize>> {
+    let mut boundaries = Vec::new();
+    let mut after_whitespace = false;
+    for (offset, c) in text[range.clone()].char_indices() {
+        if c.is_whitespace() {
+            after_whitespace = true;
+        } else if after_whitespace {
+            boundaries.push(range.start + offset);
+            after_whitespace = false;
+        }
+    }
+
+    split_at(range, boundaries)
+}
+
+/// Split a range of the text into pieces of `width` characters.
+fn characters(text: &str, range: Range<usize>, width: usize) -> Vec<Range<usize>> {
+    let start = range.start;
+    let boundaries = text[range.clone()]
+        .char_indices()
+        .step_by(width)
+        .map(|(offset, _)| start + offset);
+
+    split_at(range, boundaries)
+}
+
+/// Split a Markdown document into sections, each starting with an ATX heading (`#` to `######`), except the text before the first heading. Headings in fenced code blocks are ignored.
+fn markdown_sections(text: &str) -> Vec<Range<usize>> {
+    let mut boundaries = Vec::new();
+    let mut fence: Option<&str> = None;
+    let mut offset = 0;
+    for line in text.split_inclusive('\n') {
+        let trimmed = line.trim_start();
+        match fence {
+            Some(marker) => {
+                if trimmed.starts_with(marker) {
+                    fence = None;
+                }
+            }
+            None if trimmed.starts_with("```") => fence = Some("```"),
+            None if trimmed.starts_with("~~~") => fence = Some("~~~"),
+            None => {
+                let level = line.chars().take_while(|&c| c == '#').count();
+                let rest = &line[level..];
+                if (1..=6).contains(&level)
+                    && (rest.trim().is_empty() || rest.starts_with([' ', '\t']))
+                {
+                    boundaries.push(offset);
+                }
+            }
+        }
+        offset += line.len();
+    }
+
+    split_at(0..text.len(), boundaries)
+}
+
+/// Add the part of the text in the range to the chunks, without leading and trailing whitespace, unless it is blank.
+fn push_chunk(text: &str, range: Range<usize>, chunks: &mut Vec<TextChunk>) {
+    let slice = &text[range.clone()];
+    let start = range.start + (slice.len() - slice.trim_start().len());
+    let end = range.start + slice.trim_end().len();
+    if start < end {
+        chunks.push(TextChunk {
+            text: text[start..end].to_string(),
+            start,
+            end,
+        });
+    }
+}
@@ -0,0 +1,286 @@
+//! Ingestion of documents into a VectorDB collection, used by [`Client::ingest`](crate::Client::ingest).
+
+use crate::error::LlamaEdgeError;
+use std::{
+    fmt,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+/// Callback receiving the progress of an ingestion.
+type ProgressHandler = Arc<dyn Fn(&IngestEvent) + Send + Sync>;
+
+/// Options for ingesting documents into a VectorDB collection.
+#[derive(Clone)]
+pub struct IngestOptions {
+    chunk_capacity: usize,
+    batch_size: usize,
+    concurrency: usize,
+    extensions: Vec<String>,
+    recursive: bool,
+    on_progress: Option<ProgressHandler>,
+}
+impl IngestOptions {
+    /// Create options with the default settings: chunks of 1024 tokens, embedded 16 at a time, 2 files ingested concurrently, and directories searched recursively for `txt` and `md` files.
+    pub fn new() -> Self {
+        Self {
+            chunk_capacity: 1024,
+            batch_size: 16,
+            concurrency: 2,
+            extensions: vec!["txt".to_string(), "md".to_string()],
+            recursive: true,
+            on_progress: None,
+        }
+    }
+
+    /// Set the capacity of each chunk, as passed to [`Client::rag_chunk_file`](crate::Client::rag_chunk_file).
+    pub fn with_chunk_capacity(mut self, chunk_capacity: usize) -> Self {
+        self.chunk_capacity = chunk_capacity;
+        self
+    }
+
+    /// Set the maximum number of chunks sent in one embeddings request.
+    pub fn with_batch_size(mut self, batch_size: usize) -> Self {
+        self.batch_size = batch_size;
+        self
+    }
+
+    /// Set the maximum number of files ingested concurrently.
+    pub fn with_concurrency(mut self, concurrency: usize) -> Self {
+        self.concurrency = concurrency;
+        self
+    }
+
+    /// Set the extensions of the files ingested from a directory, without the leading dot. Files given explicitly are always ingested.
+    pub fn with_extensions(mut self, extensions: Vec<String>) -> Self {
+        self.extensions = extensions;
+        self
+    }
+
+    /// Enable or disable searching the subdirectories of a directory.
+    pub fn with_recursive(mut self, recursive: bool) -> Self {
+        self.recursive = recursive;
+        self
+    }
+
+    /// Set a callback receiving the progress of each file. It is called from the tasks ingesting the files, so it should return quickly.
+    pub fn with_progress(
+        mut self,
+        on_progress: impl Fn(&IngestEvent) + Send + Sync + 'static,
+    ) -> Self {
+        self.on_progress = Some(Arc::new(on_progress));
+        self
+    }
+
+    /// Get the capacity of each chunk.
+    pub fn chunk_capacity(&self) -> usize {
+        self.chunk_capacity
+    }
+
+    /// Get the maximum number of chunks sent in one embeddings request.
+    pub fn batch_size(&self) -> usize {
+        self.batch_size
+    }
+
+    /// Get the maximum number of files ingested concurrently.
+    pub fn concurrency(&self) -> usize {
+        self.concurrency
+    }
+
+    /// Check that the options are consistent.
+    pub(crate) fn validate(&self) -> Result<(), LlamaEdgeError> {
+        if self.chunk_capacity == 0 {
+            return Err(LlamaEdgeError::InvalidArgument(
+                "The chunk capacity cannot be zero".to_string(),
+            ));
+        }
+        if self.batch_size == 0 {
+            return Err(LlamaEdgeError::InvalidArgument(
+                "The batch size cannot be zero".to_string(),
+            ));
+        }
+        if self.concurrency == 0 {
+            return Err(LlamaEdgeError::InvalidArgument(
+                "The concurrency cannot be zero".to_string(),
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Report the progress of a file to the callback, if any.
+    pub(crate) fn report(&self, event: IngestEvent) {
+        if let Some(on_progress) = &self.on_progress {
+            on_progress(&event);
+        }
+    }
+
+    /// List the files to ingest. Directories are expanded into the files they contain with one of the accepted extensions, sorted by path. Symbolic links found in a directory are followed to files but not to directories, so a link to a parent directory cannot make the search loop. Duplicates are removed.
+    pub(crate) async fn collect_files(
+        &self,
+        paths: impl IntoIterator<Item = impl AsRef<Path>>,
+    ) -> Result<Vec<PathBuf>, LlamaEdgeError> {
+        let read_error = |path: &Path, e: std::io::Error| {
+            LlamaEdgeError::Operation(format!(
+                "Failed to read the directory {}: {}",
+                path.display(),
+                e
+            ))
+        };
+
+        let mut files = Vec::new();
+        for path in paths {
+            let path = path.as_ref();
+            let is_dir = tokio::fs::metadata(path)
+                .await
+                .is_ok_and(|metadata| metadata.is_dir());
+            if !is_dir {
+                // missing files are reported as failures of their own
+                files.push(path.to_path_buf());
+                continue;
+            }
+
+            let mut found = Vec::new();
+            let mut directories = vec![path.to_path_buf()];
+            while let Some(directory) = directories.pop() {
+                let mut entries = tokio::fs::read_dir(&directory)
+                    .await
+                    .map_err(|e| read_error(&directory, e))?;
+                while let Some(entry) = entries
+                    .next_entry()
+                    .await
+                    .map_err(|e| read_error(&directory, e))?
+                {
+                    let entry_path = entry.path();
+                    let file_type = entry
+                        .file_type()
+                        .await
+                        .map_err(|e| read_error(&directory, e))?;
+                    if file_type.is_dir() {
+                        if self.recursive {
+                            directories.push(entry_path);
+                        }
+                    } else if self.accepts(&entry_path) {
+                        let is_file = file_type.is_file()
+                            || (file_type.is_symlink()
+                                && tokio::fs::metadata(&entry_path)
+                                    .await
+                                    .is_ok_and(|metadata| metadata.is_file()));
+                        if is_file {
+                            found.push(entry_path);
+                        }
+                    }
+                }
+            }
+            found.sort();
+            files.extend(found);
+        }
+
+        let mut seen = std::collections::HashSet::new();
+        files.retain(|file| seen.insert(file.clone()));
+
+        Ok(files)
+    }
+
+    /// Check if a file found in a directory has one of the accepted extensions.
+    fn accepts(&self, path: &Path) -> bool {
+        path.extension()
+            .and_then(|extension| extension.to_str())
+            .is_some_and(|extension| {
+                self.extensions
+                    .iter()
+                    .any(|accepted| accepted.eq_ignore_ascii_case(extension))
+            })
+    }
+}
+impl Default for IngestOptions {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+impl fmt::Debug for IngestOptions {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("IngestOptions")
+            .field("chunk_capacity", &self.chunk_capacity)
+            .field("batch_size", &self.batch_size)
+            .field("concurrency", &self.concurrency)
+            .field("extensions", &self.extensions)
+            .field("recursive", &self.recursive)
+            .field("on_progress", &self.on_progress.is_some())
+            .finish()
+    }
+}
+
+/// The progress of the ingestion of a file, reported to the callback set with [`IngestOptions::with_progress`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum IngestEvent {
+    /// The file has been uploaded and split into chunks.
+    Chunked {
+        /// The path to the file.
+        path: PathBuf,
+        /// The number of chunks.
+        chunks: usize,
+    },
+    /// A batch of chunks has been embedded and indexed.
+    Embedded {
+        /// The path to the file.
+        path: PathBuf,
+        /// The number of chunks of the file indexed so far.
+        indexed: usize,
+        /// The number of chunks of the file.
+        chunks: usize,
+    },
+    /// All the chunks of the file have been indexed.
+    Completed {
+        /// The path to the file.
+        path: PathBuf,
+        /// The number of chunks indexed.
+        chunks: usize,
+    },
+    /// The ingestion of the file failed.
+    Failed {
+        /// The path to the file.
+        path: PathBuf,
+        /// The error message.
+        error: String,
+    },
+}
+
+/// A file whose chunks have all been indexed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IngestedFile {
+    /// The path to the file.
+    pub path: PathBuf,
+    /// The number of chunks indexed.
+    pub chunks: usize,
+}
+
+/// A file whose ingestion failed.
+#[derive(Debug)]
+pub struct IngestFailure {
+    /// The path to the file.
+    pub path: PathBuf,
+    /// The number of chunks indexed before the failure. They are not removed from the collection.
+    pub indexed: usize,
+    /// The error.
+    pub error: LlamaEdgeError,
+}
+
+/// The outcome of [`Client::ingest`](crate::Client::ingest).
+#[derive(Debug, Default)]
+pub struct IngestSummary {
+    /// The collection the chunks are indexed into.
+    pub collection: String,
+    /// The total number of chunks indexed, including those of failed files.
+    pub chunks: usize,
+    /// The files whose chunks have all been indexed, in the order they were given.
+    pub ingested: Vec<IngestedFile>,
+    /// The files whose ingestion failed, in the order they were given.
+    pub failed: Vec<IngestFailure>,
+}
+impl IngestSummary {
+    /// Check if every file has been ingested.
+    pub fn is_complete(&self) -> bool {
+        self.failed.is_empty()
+    }
+}
@@ -0,0 +1,456 @@
+//! A client for managing the collections of a Qdrant-compatible VectorDB server, such as the collections the LlamaEdge API server indexes embeddings into.
+//!
+//! # Example
+//!
+//! ```rust,no_run
+//! use llamaedge::rag::vdb::{Condition, Distance, Filter, VectorDbClient, VectorParams};
+//!
+//! #[tokio::main]
+//! async fn main() {
+//!     let vdb = VectorDbClient::new("http://localhost:6333").unwrap();
+//!
+//!     vdb.create_collection("paris", VectorParams::new(768, Distance::Cosine))
+//!         .await
+//!         .unwrap();
+//!     println!("{} points", vdb.count_points("paris", None).await.unwrap());
+//!
+//!     let filter = Filter::new().must(Condition::matches("source", "outdated text"));
+//!     vdb.delete_points("paris", &filter).await.unwrap();
+//! }
+//! ```
+
+use crate::{error::LlamaEdgeError, params::RagVdbConfig};
+use reqwest::Method;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::fmt;
+use url::Url;
+
+/// Client for the collections API of a Qdrant-compatible VectorDB server.
+///
+/// Cloning the client is cheap and shares the same connection pool.
+#[derive(Clone)]
+pub struct VectorDbClient {
+    server_url: Url,
+    http_client: reqwest::Client,
+    api_key: Option<String>,
+}
+impl VectorDbClient {
+    /// Create a new client.
+    ///
+    /// # Arguments
+    ///
+    /// * `server_url` - The URL of the VectorDB server, e.g. `http://localhost:6333`.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the client or an error if the URL is invalid.
+    pub fn new(server_url: impl AsRef<str>) -> Result<Self, LlamaEdgeError> {
+        let server_url = server_url.as_ref();
+        // a trailing slash marks the path of the URL as a directory, so that the collection paths are appended to it
+        let url = Url::parse(&format!("{}/", server_url.trim_end_matches('/')))?;
+        if url.cannot_be_a_base() || url.query().is_some() || url.fragment().is_some() {
+            return Err(LlamaEdgeError::InvalidArgument(format!(
+                "Invalid VectorDB server URL: {}",
+                server_url
+            )));
+        }
+
+        let http_client = reqwest::Client::builder()
+            .user_agent(crate::DEFAULT_USER_AGENT)
+            .build()
+            .map_err(|e| {
+                LlamaEdgeError::Operation(format!("Failed to build the HTTP client: {}", e))
+            })?;
+
+        Ok(Self {
+            server_url: url,
+            http_client,
+            api_key: None,
+        })
+    }
+
+    /// Create a client for the VectorDB server of a RAG configuration, using its API key if any.
+    pub fn from_config(config: &RagVdbConfig) -> Result<Self, LlamaEdgeError> {
+        let client = Self::new(&config.server_url)?;
+        Ok(match &config.api_key {
+            Some(api_key) => client.with_api_key(api_key),
+            None => client,
+        })
+    }
+
+    /// Set the API key sent in the `api-key` header of every request.
+    pub fn with_api_key(mut self, api_key: impl Into<String>) -> Self {
+        self.api_key = Some(api_key.into());
+        self
+    }
+
+    /// Use a preconfigured `reqwest::Client`, e.g. to set timeouts or a proxy.
+    pub fn with_http_client(mut self, http_client: reqwest::Client) -> Self {
+        self.http_client = http_client;
+        self
+    }
+
+    /// Get the URL of the VectorDB server.
+    pub fn server_url(&self) -> &Url {
+        &self.server_url
+    }
+
+    /// List the names of the collections.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the names of the collections or an error.
+    pub async fn list_collections(&self) -> Result<Vec<String>, LlamaEdgeError> {
+        #[derive(Deserialize)]
+        struct Collections {
+            collections: Vec<CollectionDescription>,
+        }
+        #[derive(Deserialize)]
+        struct CollectionDescription {
+            name: String,
+        }
+
+        let collections: Collections = self.send(Method::GET, &["collections"], None, &[]).await?;
+
+        Ok(collections
+            .collections
+            .into_iter()
+            .map(|collection| collection.name)
+            .collect())
+    }
+
+    /// Get the status, size and configuration of a collection.
+    ///
+    /// # Arguments
+    ///
+    /// * `collection_name` - The name of the collection.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the information about the collection or an error.
+    pub async fn collection_info(
+        &self,
+        collection_name: &str,
+    ) -> Result<CollectionInfo, LlamaEdgeError> {
+        check_collection_name(collection_name)?;
+
+        self.send(Method::GET, &["collections", collection_name], None, &[])
+            .await
+    }
+
+    /// Create a collection.
+    ///
+    /// # Arguments
+    ///
+    /// * `collection_name` - The name of the collection.
+    ///
+    /// * `vectors` - The size of the vectors and the distance used to compare them. The size must be the dimension of the embeddings of the model.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing `true` if the collection was created, or an error, e.g. if it already exists.
+    pub async fn create_collection(
+        &self,
+        collection_name: &str,
+        vectors: VectorParams,
+    ) -> Result<bool, LlamaEdgeError> {
+        check_collection_name(collection_name)?;
+        if vectors.size == 0 {
+            return Err(LlamaEdgeError::InvalidArgument(
+                "The vector size cannot be zero".to_string(),
+            ));
+        }
+
+        self.send(
+            Method::PUT,
+            &["collections", collection_name],
+            Some(json!({ "vectors": vectors })),
+            &[],
+        )
+        .await
+    }
+
+    /// Delete a collection and all its points.
+    ///
+    /// # Arguments
+    ///
+    /// * `collection_name` - The name of the collection.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing `true` if the collection was deleted, `false` if it did not exist, or an error.
+    pub async fn delete_collection(&self, collection_name: &str) -> Result<bool, LlamaEdgeError> {
+        check_collection_name(collection_name)?;
+
+        self.send(Method::DELETE, &["collections", collection_name], None, &[])
+            .await
+    }
+
+    /// Count the points of a collection.
+    ///
+    /// # Arguments
+    ///
+    /// * `collection_name` - The name of the collection.
+    ///
+    /// * `filter` - The filter the counted points must match, or `None` to count all the points.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the exact number of points or an error.
+    pub async fn count_points(
+        &self,
+        collection_name: &str,
+        filter: Option<&Filter>,
+    ) -> Result<u64, LlamaEdgeError> {
+        #[derive(Deserialize)]
+        struct Count {
+            count: u64,
+        }
+
+        check_collection_name(collection_name)?;
+
+        let mut body = json!({ "exact": true });
+        if let Some(filter) = filter {
+            body["filter"] = json!(filter);
+        }
+        let count: Count = self
+            .send(
+                Method::POST,
+                &["collections", collection_name, "points", "count"],
+                Some(body),
+                &[],
+            )
+            .await?;
+
+        Ok(count.count)
+    }
+
+    /// Delete the points of a collection matching a filter, and wait for the deletion to be applied.
+    ///
+    /// # Arguments
+    ///
+    /// * `collection_name` - The name of the collection.
+    ///
+    /// * `filter` - The filter the deleted points must match. It cannot be empty: use [`VectorDbClient::delete_collection`] to delete all the points.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the outcome of the operation or an error.
+    pub async fn delete_points(
+        &self,
+        collection_name: &str,
+        filter: &Filter,
+    ) -> Result<UpdateResult, LlamaEdgeError> {
+        check_collection_name(collection_name)?;
+        if filter.is_empty() {
+            return Err(LlamaEdgeError::InvalidArgument(
+                "The filter of the points to delete cannot be empty".to_string(),
+            ));
+        }
+
+        self.send(
+            Method::POST,
+            &["collections", collection_name, "points", "delete"],
+            Some(json!({ "filter": filter })),
+            &[("wait", "true")],
+        )
+        .await
+    }
+
+    /// Send a request to the given path and decode the `result` of the response.
+    async fn send<T: DeserializeOwned>(
+        &self,
+        method: Method,
+        path: &[&str],
+        body: Option<Value>,
+        query: &[(&str, &str)],
+    ) -> Result<T, LlamaEdgeError> {
+        #[derive(Deserialize)]
+        struct Response<T> {
+            result: T,
+        }
+
+        // the collection name is percent-encoded, so it cannot escape its path segment
+        let mut url = self.server_url.clone();
+        url.path_segments_mut()
+            .map_err(|_| LlamaEdgeError::Operation("Failed to build the VectorDB URL".to_string()))?
+            .pop_if_empty()
+            .extend(path);
+        if !query.is_empty() {
+            url.query_pairs_mut().extend_pairs(query);
+        }
+
+        let mut request = self.http_client.request(method, url);
+        if let Some(api_key) = &self.api_key {
+            request = request.header("api-key", api_key);
+        }
+        if let Some(body) = body {
+            request = request.json(&body);
+        }
+
+        let response = crate::Client::send_once(request).await?;
+        let response = crate::decode_json::<Response<T>>(response).await?;
+
+        Ok(response.result)
+    }
+}
+impl fmt::Debug for VectorDbClient {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("VectorDbClient")
+            .field("server_url", &self.server_url)
+            .field("api_key", &self.api_key.as_ref().map(|_| "<redacted>"))
+            .finish_non_exhaustive()
+    }
+}
+
+/// The distance used to compare the vectors of a collection.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Distance {
+    /// The cosine similarity.
+    Cosine,
+    /// The Euclidean distance.
+    Euclid,
+    /// The dot product.
+    Dot,
+    /// The Manhattan distance.
+    Manhattan,
+}
+
+/// The configuration of the vectors of a collection.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct VectorParams {
+    /// The dimension of the vectors.
+    pub size: u64,
+    /// The distance used to compare the vectors.
+    pub distance: Distance,
+}
+impl VectorParams {
+    /// Create the configuration of vectors of the given dimension.
+    pub fn new(size: u64, distance: Distance) -> Self {
+        Self { size, distance }
+    }
+}
+
+/// Information about a collection, as returned by [`VectorDbClient::collection_info`].
+#[derive(Debug, Clone, Deserialize)]
+pub struct CollectionInfo {
+    /// The status of the collection, e.g. `green` when it is ready.
+    pub status: String,
+    /// The approximate number of points. Use [`VectorDbClient::count_points`] for an exact count.
+    #[serde(default)]
+    pub points_count: Option<u64>,
+    /// The number of indexed vectors.
+    #[serde(default)]
+    pub indexed_vectors_count: Option<u64>,
+    /// The number of segments.
+    #[serde(default)]
+    pub segments_count: Option<u64>,
+    /// The configuration of the collection, as returned by the server.
+    #[serde(default)]
+    pub config: Value,
+}
+impl CollectionInfo {
+    /// Get the configuration of the vectors, if the collection has a single unnamed vector per point.
+    pub fn vector_params(&self) -> Option<VectorParams> {
+        self.config
+            .pointer("/params/vectors")
+            .and_then(|vectors| serde_json::from_value(vectors.clone()).ok())
+    }
+}
+
+/// The outcome of an operation updating the points of a collection.
+#[derive(Debug, Clone, Deserialize)]
+pub struct UpdateResult {
+    /// The sequential number of the operation, if any.
+    #[serde(default)]
+    pub operation_id: Option<u64>,
+    /// The status of the operation, `completed` once it has been applied.
+    pub status: String,
+}
+
+/// A condition on the points of a collection.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(transparent)]
+pub struct Condition(Value);
+impl Condition {
+    /// Match the points whose payload field `key` is equal to `value`, e.g. the `source` text of the chunks indexed by the LlamaEdge API server.
+    pub fn matches(key: impl Into<String>, value: impl Into<Value>) -> Self {
+        Self(json!({ "key": key.into(), "match": { "value": value.into() } }))
+    }
+
+    /// Match the points whose payload field `key` is equal to one of `values`.
+    pub fn matches_any<V: Into<Value>>(
+        key: impl Into<String>,
+        values: impl IntoIterator<Item = V>,
+    ) -> Self {
+        let values = values.into_iter().map(Into::into).collect::<Vec<Value>>();
+        Self(json!({ "key": key.into(), "match": { "any": values } }))
+    }
+
+    /// Match the points with one of the given IDs.
+    pub fn has_id(ids: impl IntoIterator<Item = u64>) -> Self {
+        Self(json!({ "has_id": ids.into_iter().collect::<Vec<_>>() }))
+    }
+
+    /// Match the points satisfying a nested filter.
+    pub fn filter(filter: Filter) -> Self {
+        Self(json!(filter))
+    }
+
+    /// Use a condition in the JSON format of the server, for conditions not covered by the other constructors.
+    pub fn raw(condition: Value) -> Self {
+        Self(condition)
+    }
+}
+
+/// A filter on the points of a collection, combining conditions.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct Filter {
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    must: Vec<Condition>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    should: Vec<Condition>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    must_not: Vec<Condition>,
+}
+impl Filter {
+    /// Create an empty filter, matching all the points.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a condition all the matched points must satisfy.
+    pub fn must(mut self, condition: Condition) -> Self {
+        self.must.push(condition);
+        self
+    }
+
+    /// Add a condition of which the matched points must satisfy at least one.
+    pub fn should(mut self, condition: Condition) -> Self {
+        self.should.push(condition);
+        self
+    }
+
+    /// Add a condition none of the matched points may satisfy.
+    pub fn must_not(mut self, condition: Condition) -> Self {
+        self.must_not.push(condition);
+        self
+    }
+
+    /// Check if the filter has no condition, and so matches all the points.
+    pub fn is_empty(&self) -> bool {
+        self.must.is_empty() && self.should.is_empty() && self.must_not.is_empty()
+    }
+}
+
+/// Check that a collection name is not blank.
+fn check_collection_name(collection_name: &str) -> Result<(), LlamaEdgeError> {
+    if collection_name.trim().is_empty() {
+        return Err(LlamaEdgeError::InvalidArgument(
+            "collection_name cannot be empty".to_string(),
+        ));
+    }
+
+    Ok(())
+}
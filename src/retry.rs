@@ -0,0 +1,144 @@
+//! Retry policies for requests failing with transient errors.
+
+use crate::error::LlamaEdgeError;
+use reqwest::StatusCode;
+use std::{
+    collections::hash_map::RandomState,
+    hash::{BuildHasher, Hasher},
+    time::Duration,
+};
+
+/// Policy for retrying requests that failed with a transient error, using exponential backoff.
+///
+/// Only the sending of a request and the receiving of the response status are retried. A streamed response is never retried once it has been returned, and requests with a multipart body, such as audio and file uploads, are sent only once.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RetryPolicy {
+    max_attempts: u32,
+    base_delay: Duration,
+    max_delay: Duration,
+    jitter: bool,
+    retryable_status_codes: Vec<StatusCode>,
+    retry_connection_errors: bool,
+    retry_timeouts: bool,
+}
+impl RetryPolicy {
+    /// Create a policy with the default settings: 3 attempts, a base delay of 500 milliseconds doubled after each attempt up to 10 seconds, full jitter, and retries on connection errors, timeouts and the status codes `408`, `429`, `502`, `503` and `504`.
+    pub fn new() -> Self {
+        Self {
+            max_attempts: 3,
+            base_delay: Duration::from_millis(500),
+            max_delay: Duration::from_secs(10),
+            jitter: true,
+            retryable_status_codes: vec![
+                StatusCode::REQUEST_TIMEOUT,
+                StatusCode::TOO_MANY_REQUESTS,
+                StatusCode::BAD_GATEWAY,
+                StatusCode::SERVICE_UNAVAILABLE,
+                StatusCode::GATEWAY_TIMEOUT,
+            ],
+            retry_connection_errors: true,
+            retry_timeouts: true,
+        }
+    }
+
+    /// Create a policy that never retries.
+    pub fn none() -> Self {
+        Self::new().with_max_attempts(1)
+    }
+
+    /// Set the maximum number of attempts, including the first one. A value of `0` is treated as `1`.
+    pub fn with_max_attempts(mut self, max_attempts: u32) -> Self {
+        self.max_attempts = max_attempts.max(1);
+        self
+    }
+
+    /// Set the delay before the first retry. The delay is doubled after each attempt.
+    pub fn with_base_delay(mut self, base_delay: Duration) -> Self {
+        self.base_delay = base_delay;
+        self
+    }
+
+    /// Set the upper bound of the delay between two attempts.
+    pub fn with_max_delay(mut self, max_delay: Duration) -> Self {
+        self.max_delay = max_delay;
+        self
+    }
+
+    /// Enable or disable jitter. With jitter, each delay is drawn uniformly between zero and the backoff delay, so that clients do not retry in lockstep.
+    pub fn with_jitter(mut self, jitter: bool) -> Self {
+        self.jitter = jitter;
+        self
+    }
+
+    /// Set the HTTP status codes that are retried.
+    pub fn with_retryable_status_codes(mut self, status_codes: Vec<StatusCode>) -> Self {
+        self.retryable_status_codes = status_codes;
+        self
+    }
+
+    /// Enable or disable retries on connection errors, such as a refused connection while the server restarts.
+    pub fn with_retry_connection_errors(mut self, retry: bool) -> Self {
+        self.retry_connection_errors = retry;
+        self
+    }
+
+    /// Enable or disable retries on timeouts.
+    pub fn with_retry_timeouts(mut self, retry: bool) -> Self {
+        self.retry_timeouts = retry;
+        self
+    }
+
+    /// Get the maximum number of attempts, including the first one.
+    pub fn max_attempts(&self) -> u32 {
+        self.max_attempts
+    }
+
+    /// Check if a request that failed with the given error should be retried.
+    pub fn should_retry(&self, error: &LlamaEdgeError) -> bool {
+        match error {
+            LlamaEdgeError::Http { status, .. } => self.retryable_status_codes.contains(status),
+            LlamaEdgeError::Transport(e) => {
+                self.retry_connection_errors && crate::error::is_connection_error(e)
+            }
+            LlamaEdgeError::Timeout(_) => self.retry_timeouts,
+            _ => false,
+        }
+    }
+
+    /// Get the delay before the next attempt.
+    ///
+    /// # Arguments
+    ///
+    /// * `attempt` - The number of the attempt that failed, starting from `1`.
+    pub fn delay(&self, attempt: u32) -> Duration {
+        let factor = 2u32.saturating_pow(attempt.saturating_sub(1));
+        let backoff = self
+            .base_delay
+            .checked_mul(factor)
+            .unwrap_or(self.max_delay)
+            .min(self.max_delay);
+
+        if self.jitter {
+            backoff.mul_f64(random_fraction())
+        } else {
+            backoff
+        }
+    }
+}
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Get a pseudo-random number in `[0, 1)`, seeded by the randomly keyed hasher of the standard library.
+fn random_fraction() -> f64 {
+    let mut hasher = RandomState::new().build_hasher();
+    hasher.write_u128(
+        std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_nanos(),
+    );
+    (hasher.finish() >> 11) as f64 / (1u64 << 53) as f64
+}
@@ -0,0 +1,144 @@
+//! Server-Sent Events decoding for streamed chat completions.
+
+use crate::error::LlamaEdgeError;
+use bytes::Bytes;
+use endpoints::chat::ChatCompletionChunk;
+use futures::{stream::Stream, StreamExt};
+use std::{
+    collections::VecDeque,
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+/// The payload the server sends to mark the end of a stream.
+const DONE_MARKER: &str = "[DONE]";
+
+/// A stream of chat completion chunks decoded from a Server-Sent Events response.
+///
+/// The raw bytes are buffered across network frames, so an event split over several frames, or a multibyte character on a frame boundary, is decoded correctly. The stream ends after the `data: [DONE]` event. If `include_usage` was requested, the final chunk carrying the token usage is yielded before the stream ends.
+pub struct ChatCompletionStream {
+    inner: Pin<Box<dyn Stream<Item = Result<Bytes, LlamaEdgeError>> + Send>>,
+    buffer: Vec<u8>,
+    pending: VecDeque<Result<ChatCompletionChunk, LlamaEdgeError>>,
+    done: bool,
+}
+impl ChatCompletionStream {
+    /// Create a new chat completion stream from a stream of raw bytes.
+    ///
+    /// # Arguments
+    ///
+    /// * `inner` - The stream of raw bytes of a Server-Sent Events response body.
+    ///
+    /// # Returns
+    ///
+    /// A new `ChatCompletionStream`.
+    pub fn new<S>(inner: S) -> Self
+    where
+        S: Stream<Item = Result<Bytes, LlamaEdgeError>> + Send + 'static,
+    {
+        Self {
+            inner: Box::pin(inner),
+            buffer: Vec::new(),
+            pending: VecDeque::new(),
+            done: false,
+        }
+    }
+
+    /// Decode all complete events in the buffer.
+    fn decode_events(&mut self) {
+        while let Some((end, delimiter_len)) = find_event_boundary(&self.buffer) {
+            let event: Vec<u8> = self.buffer.drain(..end + delimiter_len).take(end).collect();
+            self.decode_event(&event);
+            if self.done {
+                self.buffer.clear();
+                break;
+            }
+        }
+    }
+
+    /// Decode a single event. Comment lines and fields other than `data` are ignored.
+    fn decode_event(&mut self, event: &[u8]) {
+        let event = match std::str::from_utf8(event) {
+            Ok(event) => event,
+            Err(e) => {
+                self.pending.push_back(Err(LlamaEdgeError::Decode {
+                    message: format!("Invalid UTF-8 in the event stream: {}", e),
+                    body: String::from_utf8_lossy(event).to_string(),
+                }));
+                return;
+            }
+        };
+
+        let mut data_lines = Vec::new();
+        for line in event.lines() {
+            if let Some(value) = line.strip_prefix("data:") {
+                data_lines.push(value.strip_prefix(' ').unwrap_or(value));
+            }
+        }
+        if data_lines.is_empty() {
+            return;
+        }
+
+        let data = data_lines.join("\n");
+        let data = data.trim();
+        if data == DONE_MARKER {
+            self.done = true;
+            return;
+        }
+
+        let chunk =
+            serde_json::from_str::<ChatCompletionChunk>(data).map_err(|e| LlamaEdgeError::Decode {
+                message: format!("Failed to parse the chat completion chunk: {}", e),
+                body: data.to_string(),
+            });
+        self.pending.push_back(chunk);
+    }
+}
+impl Stream for ChatCompletionStream {
+    type Item = Result<ChatCompletionChunk, LlamaEdgeError>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        loop {
+            if let Some(item) = self.pending.pop_front() {
+                return Poll::Ready(Some(item));
+            }
+            if self.done {
+                return Poll::Ready(None);
+            }
+
+            match self.inner.poll_next_unpin(cx) {
+                Poll::Ready(Some(Ok(bytes))) => {
+                    self.buffer.extend_from_slice(&bytes);
+                    self.decode_events();
+                }
+                Poll::Ready(Some(Err(e))) => {
+                    self.done = true;
+                    return Poll::Ready(Some(Err(e)));
+                }
+                Poll::Ready(None) => {
+                    // flush the last event if the server closed the stream without a trailing blank line
+                    self.done = true;
+                    let rest = std::mem::take(&mut self.buffer);
+                    if !rest.iter().all(u8::is_ascii_whitespace) {
+                        self.decode_event(&rest);
+                    }
+                }
+                Poll::Pending => return Poll::Pending,
+            }
+        }
+    }
+}
+
+/// Find the end of the first event in the buffer, returning the position of the blank line and its length.
+fn find_event_boundary(buffer: &[u8]) -> Option<(usize, usize)> {
+    let mut i = 0;
+    while i < buffer.len() {
+        for delimiter in [&b"\r\n\r\n"[..], b"\n\n", b"\r\r"] {
+            if buffer[i..].starts_with(delimiter) {
+                return Some((i, delimiter.len()));
+            }
+        }
+        i += 1;
+    }
+    None
+}
@@ -0,0 +1,182 @@
+//! Registry of tools the model may call, used by [`Client::run_with_tools`](crate::Client::run_with_tools).
+
+use crate::error::LlamaEdgeError;
+use endpoints::chat::{ChatCompletionObject, ChatCompletionRequestMessage, Tool, ToolCall};
+use futures::future::BoxFuture;
+use serde_json::{json, Value};
+use std::{fmt, future::Future, sync::Arc};
+
+/// Async function executing a tool call. It receives the arguments generated by the model and returns the result sent back to the model.
+type ToolHandler =
+    Arc<dyn Fn(Value) -> BoxFuture<'static, Result<String, LlamaEdgeError>> + Send + Sync>;
+
+/// A tool definition together with the function executing it.
+struct RegisteredTool {
+    name: String,
+    tool: Tool,
+    handler: ToolHandler,
+}
+
+/// Registry of async functions the model may call.
+///
+/// # Example
+///
+/// ```rust
+/// use llamaedge::tools::ToolRegistry;
+/// use serde_json::json;
+///
+/// let mut registry = ToolRegistry::new();
+/// registry
+///     .register(
+///         "get_current_weather",
+///         "Get the current weather in a given location",
+///         json!({
+///             "type": "object",
+///             "properties": {
+///                 "location": {
+///                     "type": "string",
+///                     "description": "The city and state, e.g. San Francisco, CA"
+///                 }
+///             },
+///             "required": ["location"]
+///         }),
+///         |args| async move {
+///             let location = args["location"].as_str().unwrap_or_default();
+///             Ok(format!("It is sunny in {}", location))
+///         },
+///     )
+///     .unwrap();
+/// assert_eq!(registry.tools().len(), 1);
+/// ```
+#[derive(Default, Clone)]
+pub struct ToolRegistry {
+    tools: Vec<Arc<RegisteredTool>>,
+}
+impl ToolRegistry {
+    /// Create an empty registry.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register an async function under the given function name. A function registered under an existing name replaces it.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name of the function the model calls.
+    ///
+    /// * `description` - The description of what the function does, used by the model to choose when and how to call it.
+    ///
+    /// * `parameters` - The parameters the function accepts, described as a JSON Schema object.
+    ///
+    /// * `handler` - The async function executing the call. It receives the parsed JSON arguments.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing nothing or an error if the parameters are not a valid JSON Schema object.
+    pub fn register<F, Fut>(
+        &mut self,
+        name: impl Into<String>,
+        description: impl Into<String>,
+        parameters: Value,
+        handler: F,
+    ) -> Result<(), LlamaEdgeError>
+    where
+        F: Fn(Value) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<String, LlamaEdgeError>> + Send + 'static,
+    {
+        let name = name.into();
+        if name.is_empty() {
+            return Err(LlamaEdgeError::InvalidArgument(
+                "The tool name cannot be empty".to_string(),
+            ));
+        }
+
+        let tool = serde_json::from_value::<Tool>(json!({
+            "type": "function",
+            "function": {
+                "name": name,
+                "description": description.into(),
+                "parameters": parameters,
+            }
+        }))
+        .map_err(|e| {
+            LlamaEdgeError::InvalidArgument(format!(
+                "Invalid parameters schema for the tool `{}`: {}",
+                name, e
+            ))
+        })?;
+
+        let handler: ToolHandler = Arc::new(move |args| Box::pin(handler(args)));
+        self.tools.retain(|registered| registered.name != name);
+        self.tools.push(Arc::new(RegisteredTool {
+            name,
+            tool,
+            handler,
+        }));
+
+        Ok(())
+    }
+
+    /// Get the definitions of the registered tools, in registration order.
+    pub fn tools(&self) -> Vec<Tool> {
+        self.tools
+            .iter()
+            .map(|registered| registered.tool.clone())
+            .collect()
+    }
+
+    /// Check if no tools are registered.
+    pub fn is_empty(&self) -> bool {
+        self.tools.is_empty()
+    }
+
+    /// Execute a tool call generated by the model.
+    ///
+    /// # Arguments
+    ///
+    /// * `tool_call` - The tool call to execute.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the result of the call or an error if the function is unknown, the arguments are not valid JSON or the function failed.
+    pub async fn call(&self, tool_call: &ToolCall) -> Result<String, LlamaEdgeError> {
+        let name = &tool_call.function.name;
+        let registered = self
+            .tools
+            .iter()
+            .find(|registered| &registered.name == name)
+            .ok_or_else(|| LlamaEdgeError::InvalidArgument(format!("Unknown tool: {}", name)))?;
+
+        let arguments = tool_call.function.arguments.trim();
+        let arguments = if arguments.is_empty() {
+            Value::Object(Default::default())
+        } else {
+            serde_json::from_str::<Value>(arguments).map_err(|e| {
+                LlamaEdgeError::InvalidArgument(format!(
+                    "Invalid arguments for the tool `{}`: {}",
+                    name, e
+                ))
+            })?
+        };
+
+        (registered.handler)(arguments).await
+    }
+}
+impl fmt::Debug for ToolRegistry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list()
+            .entries(self.tools.iter().map(|registered| &registered.name))
+            .finish()
+    }
+}
+
+/// The outcome of [`Client::run_with_tools`](crate::Client::run_with_tools).
+#[derive(Debug)]
+pub struct ToolRun {
+    /// The chat history extended with the tool calls, the tool results and the final answer of the model.
+    pub messages: Vec<ChatCompletionRequestMessage>,
+    /// The chat completion carrying the final answer.
+    pub chat_completion: ChatCompletionObject,
+    /// The number of chat completion requests sent.
+    pub iterations: usize,
+}
@@ -0,0 +1,472 @@
+//! Files sent to the server in multipart requests, such as uploads, audio and images.
+
+use crate::error::LlamaEdgeError;
+use bytes::Bytes;
+use futures::{stream, StreamExt};
+use reqwest::multipart;
+use std::{
+    fmt,
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+};
+use tokio::io::{AsyncRead, AsyncReadExt};
+
+/// The content type used when it can be detected neither from the content nor from the file extension.
+const DEFAULT_MIME_TYPE: &str = "application/octet-stream";
+
+/// The number of bytes read from a file or a reader at a time while streaming it to the server.
+const CHUNK_SIZE: usize = 64 * 1024;
+
+/// The number of bytes at the start of the content used to detect its content type.
+const SNIFF_SIZE: usize = 16;
+
+/// A reader streamed as the content of a file.
+type BoxedReader = Box<dyn AsyncRead + Send + Unpin>;
+
+/// A reader shared by the clones of an [`UploadFile`]. It is taken by the first clone that is sent.
+type SharedReader = Arc<Mutex<Option<BoxedReader>>>;
+
+/// The source of the content of an [`UploadFile`].
+#[derive(Clone)]
+enum Source {
+    Path(PathBuf),
+    Bytes {
+        bytes: Bytes,
+        filename: String,
+    },
+    Reader {
+        reader: SharedReader,
+        filename: String,
+        length: Option<u64>,
+    },
+}
+
+/// A file to send to the server, read from a path, from memory or from an async reader.
+///
+/// Files read from a path or a reader are streamed as the request body rather than buffered in memory. The content type of the file is detected from its content and its filename, unless it is set explicitly with [`UploadFile::with_mime_type`].
+///
+/// The clones of a file created from a reader share the reader, so only one of them can be sent.
+#[derive(Clone)]
+pub struct UploadFile {
+    source: Source,
+    mime_type: Option<String>,
+}
+impl UploadFile {
+    /// Create a file to upload from the given path.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The path to the file.
+    pub fn from_path(path: impl AsRef<Path>) -> Self {
+        Self {
+            source: Source::Path(path.as_ref().to_path_buf()),
+            mime_type: None,
+        }
+    }
+
+    /// Create a file to upload from content held in memory.
+    ///
+    /// # Arguments
+    ///
+    /// * `bytes` - The content of the file.
+    ///
+    /// * `filename` - The name of the file sent to the server.
+    pub fn from_bytes(bytes: impl Into<Bytes>, filename: impl Into<String>) -> Self {
+        Self {
+            source: Source::Bytes {
+                bytes: bytes.into(),
+                filename: filename.into(),
+            },
+            mime_type: None,
+        }
+    }
+
+    /// Create a file to upload from an async reader, such as a network stream. The reader is streamed to the server until it reaches the end.
+    ///
+    /// # Arguments
+    ///
+    /// * `reader` - The reader of the content of the file.
+    ///
+    /// * `filename` - The name of the file sent to the server.
+    pub fn from_reader(
+        reader: impl AsyncRead + Send + Unpin + 'static,
+        filename: impl Into<String>,
+    ) -> Self {
+        Self {
+            source: Source::Reader {
+                reader: Arc::new(Mutex::new(Some(Box::new(reader)))),
+                filename: filename.into(),
+                length: None,
+            },
+            mime_type: None,
+        }
+    }
+
+    /// Set the content type of the file, overriding the detected one.
+    pub fn with_mime_type(mut self, mime_type: impl Into<String>) -> Self {
+        self.mime_type = Some(mime_type.into());
+        self
+    }
+
+    /// Set the length of the content of a file created with [`UploadFile::from_reader`], so that the request is sent with a `Content-Length` header instead of a chunked body. It has no effect on the other sources, whose length is known.
+    pub fn with_length(mut self, length: u64) -> Self {
+        if let Source::Reader { length: l, .. } = &mut self.source {
+            *l = Some(length);
+        }
+        self
+    }
+
+    /// Get the path to the file, if it is read from a path.
+    pub fn path(&self) -> Option<&Path> {
+        match &self.source {
+            Source::Path(path) => Some(path),
+            _ => None,
+        }
+    }
+
+    /// Get the name of the file sent to the server, if it can be determined.
+    pub fn filename(&self) -> Option<&str> {
+        match &self.source {
+            Source::Path(path) => path.file_name().and_then(|filename| filename.to_str()),
+            Source::Bytes { filename, .. } | Source::Reader { filename, .. } => Some(filename),
+        }
+    }
+
+    /// Get the content type of the file, if it is set explicitly.
+    pub fn mime_type(&self) -> Option<&str> {
+        self.mime_type.as_deref()
+    }
+
+    /// Read the whole content of the file into memory, together with its filename.
+    ///
+    /// # Arguments
+    ///
+    /// * `kind` - The kind of file, such as `audio file`, used in error messages.
+    #[cfg(any(feature = "audio", feature = "image"))]
+    pub(crate) async fn read_all(self, kind: &str) -> Result<(Bytes, String), LlamaEdgeError> {
+        let read_error = |e: std::io::Error| {
+            LlamaEdgeError::Operation(format!("Failed to read the {}: {}", kind, e))
+        };
+
+        match self.source {
+            Source::Path(path) => {
+                if !path.is_file() {
+                    return Err(LlamaEdgeError::InvalidArgument(format!(
+                        "The {} does not exist: {}",
+                        kind,
+                        path.display()
+                    )));
+                }
+
+                let filename = path_filename(&path, kind)?;
+                let bytes = tokio::fs::read(&path).await.map_err(read_error)?;
+                Ok((Bytes::from(bytes), filename))
+            }
+            Source::Bytes { bytes, filename } => Ok((bytes, filename)),
+            Source::Reader {
+                reader, filename, ..
+            } => {
+                let mut reader = take_reader(&reader, kind)?;
+                let mut bytes = Vec::new();
+                reader.read_to_end(&mut bytes).await.map_err(read_error)?;
+                Ok((Bytes::from(bytes), filename))
+            }
+        }
+    }
+
+    /// Read the whole content of the file into memory, and get a file with the same content, filename and content type to send instead.
+    ///
+    /// # Arguments
+    ///
+    /// * `kind` - The kind of file, such as `image file`, used in error messages.
+    #[cfg(feature = "image")]
+    pub(crate) async fn buffer(self, kind: &str) -> Result<(Self, Bytes), LlamaEdgeError> {
+        let mime_type = self.mime_type.clone();
+        let (bytes, filename) = self.read_all(kind).await?;
+        let file = Self {
+            source: Source::Bytes {
+                bytes: bytes.clone(),
+                filename,
+            },
+            mime_type,
+        };
+
+        Ok((file, bytes))
+    }
+
+    /// Create a multipart part from the file.
+    ///
+    /// # Arguments
+    ///
+    /// * `kind` - The kind of file, such as `audio file`, used in error messages.
+    pub(crate) async fn into_part(self, kind: &str) -> Result<multipart::Part, LlamaEdgeError> {
+        let mime_type = self.mime_type;
+        match self.source {
+            Source::Path(path) => {
+                if !path.is_file() {
+                    return Err(LlamaEdgeError::InvalidArgument(format!(
+                        "The {} does not exist: {}",
+                        kind,
+                        path.display()
+                    )));
+                }
+
+                let filename = path_filename(&path, kind)?;
+
+                let file = tokio::fs::File::open(&path).await.map_err(|e| {
+                    LlamaEdgeError::Operation(format!("Failed to read the {}: {}", kind, e))
+                })?;
+                let length = file
+                    .metadata()
+                    .await
+                    .map_err(|e| {
+                        LlamaEdgeError::Operation(format!("Failed to read the {}: {}", kind, e))
+                    })?
+                    .len();
+
+                reader_part(Box::new(file), filename, mime_type, Some(length), kind).await
+            }
+            Source::Bytes { bytes, filename } => {
+                let mime_type = match mime_type {
+                    Some(mime_type) => mime_type,
+                    None => detect_mime_type(&filename, &bytes).to_string(),
+                };
+
+                let length = bytes.len() as u64;
+                part_with_mime_type(
+                    multipart::Part::stream_with_length(bytes, length),
+                    filename,
+                    mime_type,
+                )
+            }
+            Source::Reader {
+                reader,
+                filename,
+                length,
+            } => {
+                let reader = take_reader(&reader, kind)?;
+                reader_part(reader, filename, mime_type, length, kind).await
+            }
+        }
+    }
+}
+impl fmt::Debug for UploadFile {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut debug = f.debug_struct("UploadFile");
+        match &self.source {
+            Source::Path(path) => debug.field("path", path),
+            Source::Bytes { bytes, filename } => debug
+                .field("filename", filename)
+                .field("length", &bytes.len()),
+            Source::Reader {
+                filename, length, ..
+            } => debug.field("filename", filename).field("length", length),
+        };
+        debug.field("mime_type", &self.mime_type).finish()
+    }
+}
+impl From<&Path> for UploadFile {
+    fn from(path: &Path) -> Self {
+        Self::from_path(path)
+    }
+}
+impl From<PathBuf> for UploadFile {
+    fn from(path: PathBuf) -> Self {
+        Self {
+            source: Source::Path(path),
+            mime_type: None,
+        }
+    }
+}
+impl From<&PathBuf> for UploadFile {
+    fn from(path: &PathBuf) -> Self {
+        Self::from_path(path)
+    }
+}
+impl From<&str> for UploadFile {
+    fn from(path: &str) -> Self {
+        Self::from_path(path)
+    }
+}
+impl From<String> for UploadFile {
+    fn from(path: String) -> Self {
+        Self::from(PathBuf::from(path))
+    }
+}
+impl From<&String> for UploadFile {
+    fn from(path: &String) -> Self {
+        Self::from_path(path)
+    }
+}
+
+/// Get the name of a file from its path.
+fn path_filename(path: &Path, kind: &str) -> Result<String, LlamaEdgeError> {
+    path.file_name()
+        .and_then(|filename| filename.to_str())
+        .map(|filename| filename.to_string())
+        .ok_or_else(|| {
+            LlamaEdgeError::InvalidArgument(format!(
+                "The name of the {} is not valid UTF-8: {}",
+                kind,
+                path.display()
+            ))
+        })
+}
+
+/// Take the reader of a file, which can only be read once.
+fn take_reader(reader: &SharedReader, kind: &str) -> Result<BoxedReader, LlamaEdgeError> {
+    reader
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .take()
+        .ok_or_else(|| {
+            LlamaEdgeError::InvalidArgument(format!("The {} has already been read", kind))
+        })
+}
+
+/// Create a multipart part streaming the content of a reader.
+///
+/// The first bytes are read ahead to detect the content type, and sent before the rest of the content.
+async fn reader_part(
+    mut reader: BoxedReader,
+    filename: String,
+    mime_type: Option<String>,
+    length: Option<u64>,
+    kind: &str,
+) -> Result<multipart::Part, LlamaEdgeError> {
+    let mut head = Vec::with_capacity(SNIFF_SIZE);
+    while head.len() < SNIFF_SIZE {
+        let mut buf = [0u8; SNIFF_SIZE];
+        let n = reader
+            .read(&mut buf[..SNIFF_SIZE - head.len()])
+            .await
+            .map_err(|e| {
+                LlamaEdgeError::Operation(format!("Failed to read the {}: {}", kind, e))
+            })?;
+        if n == 0 {
+            break;
+        }
+        head.extend_from_slice(&buf[..n]);
+    }
+
+    let mime_type = match mime_type {
+        Some(mime_type) => mime_type,
+        None => detect_mime_type(&filename, &head).to_string(),
+    };
+
+    let rest = stream::unfold(Some(reader), |reader| async move {
+        let mut reader = reader?;
+        let mut buf = vec![0u8; CHUNK_SIZE];
+        match reader.read(&mut buf).await {
+            Ok(0) => None,
+            Ok(n) => {
+                buf.truncate(n);
+                Some((Ok(Bytes::from(buf)), Some(reader)))
+            }
+            // stop reading after an error
+            Err(e) => Some((Err(e), None)),
+        }
+    });
+    let body = reqwest::Body::wrap_stream(
+        stream::once(async move { Ok::<_, std::io::Error>(Bytes::from(head)) }).chain(rest),
+    );
+
+    let part = match length {
+        Some(length) => multipart::Part::stream_with_length(body, length),
+        None => multipart::Part::stream(body),
+    };
+    part_with_mime_type(part, filename, mime_type)
+}
+
+/// Set the filename and the content type of a multipart part.
+fn part_with_mime_type(
+    part: multipart::Part,
+    filename: String,
+    mime_type: String,
+) -> Result<multipart::Part, LlamaEdgeError> {
+    part.file_name(filename)
+        .mime_str(&mime_type)
+        .map_err(|_| LlamaEdgeError::InvalidArgument(format!("Invalid MIME type: {}", mime_type)))
+}
+
+/// Detect the content type of a file.
+///
+/// The content type is first sniffed from the magic bytes at the start of the content, then looked up by the file extension. Content that matches neither is labelled `text/plain` if it is valid UTF-8, and `application/octet-stream` otherwise.
+///
+/// # Arguments
+///
+/// * `filename` - The name of the file.
+///
+/// * `content` - The content of the file, or its first bytes.
+pub fn detect_mime_type(filename: &str, content: &[u8]) -> &'static str {
+    if let Some(mime_type) = mime_type_from_magic_bytes(content) {
+        return mime_type;
+    }
+
+    let extension = Path::new(filename)
+        .extension()
+        .and_then(|extension| extension.to_str())
+        .map(|extension| extension.to_ascii_lowercase());
+    if let Some(mime_type) = extension.as_deref().and_then(mime_type_from_extension) {
+        return mime_type;
+    }
+
+    // the content may be cut in the middle of a multibyte character, which is not an invalid sequence
+    let is_text = match std::str::from_utf8(content) {
+        Ok(_) => true,
+        Err(e) => e.error_len().is_none(),
+    };
+    if !content.is_empty() && is_text {
+        "text/plain"
+    } else {
+        DEFAULT_MIME_TYPE
+    }
+}
+
+/// Sniff the content type from the signature at the start of the content.
+fn mime_type_from_magic_bytes(content: &[u8]) -> Option<&'static str> {
+    let mime_type = match content {
+        [0x89, b'P', b'N', b'G', 0x0D, 0x0A, 0x1A, 0x0A, ..] => "image/png",
+        [0xFF, 0xD8, 0xFF, ..] => "image/jpeg",
+        [b'G', b'I', b'F', b'8', b'7' | b'9', b'a', ..] => "image/gif",
+        [b'R', b'I', b'F', b'F', _, _, _, _, b'W', b'E', b'B', b'P', ..] => "image/webp",
+        [b'R', b'I', b'F', b'F', _, _, _, _, b'W', b'A', b'V', b'E', ..] => "audio/wav",
+        [b'I', b'D', b'3', ..] | [0xFF, 0xFB | 0xF3 | 0xF2, ..] => "audio/mpeg",
+        [b'f', b'L', b'a', b'C', ..] => "audio/flac",
+        [b'O', b'g', b'g', b'S', ..] => "audio/ogg",
+        [_, _, _, _, b'f', b't', b'y', b'p', b'M', b'4', b'A', ..] => "audio/mp4",
+        [_, _, _, _, b'f', b't', b'y', b'p', ..] => "video/mp4",
+        [0x1A, 0x45, 0xDF, 0xA3, ..] => "video/webm",
+        [b'%', b'P', b'D', b'F', b'-', ..] => "application/pdf",
+        _ => return None,
+    };
+
+    Some(mime_type)
+}
+
+/// Look up the content type of a lowercase file extension.
+fn mime_type_from_extension(extension: &str) -> Option<&'static str> {
+    let mime_type = match extension {
+        "txt" => "text/plain",
+        "md" | "markdown" => "text/markdown",
+        "csv" => "text/csv",
+        "html" | "htm" => "text/html",
+        "json" => "application/json",
+        "pdf" => "application/pdf",
+        "wav" => "audio/wav",
+        "mp3" => "audio/mpeg",
+        "m4a" => "audio/mp4",
+        "flac" => "audio/flac",
+        "ogg" | "oga" => "audio/ogg",
+        "mp4" => "video/mp4",
+        "webm" => "video/webm",
+        "png" => "image/png",
+        "jpg" | "jpeg" => "image/jpeg",
+        "gif" => "image/gif",
+        "webp" => "image/webp",
+        "bmp" => "image/bmp",
+        _ => return None,
+    };
+
+    Some(mime_type)
+}
@@ -22,7 +22,7 @@ mod tests {
         assert!(result.is_ok());
 
         let transcription = result.unwrap();
-        let text = transcription.text.to_lowercase();
+        let text = transcription.text().to_lowercase();
         assert!(text.contains("this is a test record for whisper.cpp"));
     }
 
@@ -40,7 +40,7 @@ mod tests {
         assert!(result.is_ok());
 
         let translation = result.unwrap();
-        let text = translation.text.to_lowercase();
+        let text = translation.text().to_lowercase();
         assert!(text.to_lowercase().contains("this is a chinese broadcast."));
     }
 }
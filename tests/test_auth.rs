@@ -0,0 +1,87 @@
+use llamaedge::{
+    auth::{ApiKey, AuthProvider, API_KEY_ENV_VAR},
+    Client,
+};
+use std::sync::{
+    atomic::{AtomicUsize, Ordering},
+    Arc,
+};
+
+const MODELS_RESPONSE: &str = r#"{"object":"list","data":[{"id":"llama","created":1,"object":"model","owned_by":"Not specified"}]}"#;
+
+#[tokio::test]
+async fn test_api_key() {
+    let mut server = mockito::Server::new_async().await;
+    let mock = server
+        .mock("GET", "/v1/models")
+        .match_header("authorization", "Bearer secret-key")
+        .with_status(200)
+        .with_body(MODELS_RESPONSE)
+        .create_async()
+        .await;
+
+    let client = Client::builder(server.url())
+        .with_api_key("secret-key")
+        .build()
+        .unwrap();
+    let result = client.models().await;
+    assert!(result.is_ok());
+
+    mock.assert_async().await;
+}
+
+#[test]
+fn test_api_key_from_lookup() {
+    let token = |value: &str| {
+        ApiKey::from_lookup(|name| (name == API_KEY_ENV_VAR).then(|| value.to_string()))
+            .and_then(|api_key| api_key.bearer_token())
+    };
+    assert_eq!(token(" env-key\n"), Some("env-key".to_string()));
+    assert_eq!(token("  "), None);
+    assert!(ApiKey::from_lookup(|_| None).is_none());
+}
+
+#[test]
+fn test_client_builder_debug_redacts_credentials() {
+    let builder = Client::builder("http://localhost:8080")
+        .with_api_key("secret-key")
+        .with_default_header("Authorization", "Bearer secret-header")
+        .with_default_header("x-request-source", "tests");
+    let debug = format!("{:?}", builder);
+
+    assert!(!debug.contains("secret"), "{}", debug);
+    assert!(debug.contains("tests"), "{}", debug);
+}
+
+#[tokio::test]
+async fn test_auth_provider_rotates_token() {
+    let mut server = mockito::Server::new_async().await;
+    let first = server
+        .mock("GET", "/v1/models")
+        .match_header("authorization", "Bearer token-0")
+        .with_status(200)
+        .with_body(MODELS_RESPONSE)
+        .create_async()
+        .await;
+    let second = server
+        .mock("GET", "/v1/models")
+        .match_header("authorization", "Bearer token-1")
+        .with_status(200)
+        .with_body(MODELS_RESPONSE)
+        .create_async()
+        .await;
+
+    let counter = Arc::new(AtomicUsize::new(0));
+    let client = Client::builder(server.url())
+        .with_auth_provider(move || {
+            Some(format!("token-{}", counter.fetch_add(1, Ordering::SeqCst)))
+        })
+        .build()
+        .unwrap();
+
+    assert!(client.models().await.is_ok());
+    assert!(client.models().await.is_ok());
+
+    first.assert_async().await;
+    second.assert_async().await;
+}
@@ -0,0 +1,26 @@
+//! Tests reading the process environment, kept in their own test binary so that setting variables does not race with other tests.
+
+use llamaedge::{auth::API_KEY_ENV_VAR, Client};
+
+const MODELS_RESPONSE: &str = r#"{"object":"list","data":[{"id":"llama","created":1,"object":"model","owned_by":"Not specified"}]}"#;
+
+#[tokio::test]
+async fn test_api_key_from_env() {
+    let mut server = mockito::Server::new_async().await;
+    let mock = server
+        .mock("GET", "/v1/models")
+        .match_header("authorization", "Bearer env-key")
+        .with_status(200)
+        .with_body(MODELS_RESPONSE)
+        .create_async()
+        .await;
+
+    std::env::set_var(API_KEY_ENV_VAR, "env-key");
+    let client = Client::new(server.url()).unwrap();
+    std::env::remove_var(API_KEY_ENV_VAR);
+
+    let result = client.models().await;
+    assert!(result.is_ok());
+
+    mock.assert_async().await;
+}
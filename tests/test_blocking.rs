@@ -0,0 +1,94 @@
+#![cfg(feature = "blocking")]
+
+use endpoints::chat::{
+    ChatCompletionRequestMessage, ChatCompletionUserMessage, ChatCompletionUserMessageContent,
+};
+use llamaedge::{blocking::Client, error::LlamaEdgeError, params::ChatParams};
+
+fn user_messages() -> Vec<ChatCompletionRequestMessage> {
+    vec![ChatCompletionRequestMessage::User(
+        ChatCompletionUserMessage::new(
+            ChatCompletionUserMessageContent::Text("What is the capital of France?".to_string()),
+            None,
+        ),
+    )]
+}
+
+fn chunk_json(content: &str) -> String {
+    format!(
+        r#"{{"id":"chatcmpl-1","choices":[{{"index":0,"delta":{{"content":"{}","role":"assistant"}},"logprobs":null,"finish_reason":null}}],"created":1,"model":"test","system_fingerprint":"fp","object":"chat.completion.chunk"}}"#,
+        content
+    )
+}
+
+#[test]
+fn test_blocking_chat() {
+    let mut server = mockito::Server::new();
+    let mock = server
+        .mock("POST", "/v1/chat/completions")
+        .with_status(200)
+        .with_body(
+            r#"{"id":"chatcmpl-1","object":"chat.completion","created":1,"model":"test","choices":[{"index":0,"message":{"role":"assistant","content":"Paris"},"finish_reason":"stop","logprobs":null}],"usage":{"prompt_tokens":3,"completion_tokens":1,"total_tokens":4}}"#,
+        )
+        .create();
+
+    let client = Client::new(server.url()).unwrap();
+    let answer = client
+        .chat(&user_messages(), &ChatParams::default())
+        .unwrap();
+    assert_eq!(answer, "Paris");
+
+    mock.assert();
+}
+
+#[test]
+fn test_blocking_chat_stream() {
+    let mut server = mockito::Server::new();
+    let body = format!(
+        "data: {}\n\ndata: {}\n\ndata: [DONE]\n\n",
+        chunk_json("Par"),
+        chunk_json("is")
+    );
+    let mock = server
+        .mock("POST", "/v1/chat/completions")
+        .with_status(200)
+        .with_header("content-type", "text/event-stream")
+        .with_body(body)
+        .create();
+
+    let client = Client::new(server.url()).unwrap();
+    let content = client
+        .chat_stream(&user_messages(), &ChatParams::default())
+        .unwrap()
+        .map(|chunk| {
+            let chunk = chunk.unwrap();
+            chunk.choices[0].delta.content.clone().unwrap_or_default()
+        })
+        .collect::<String>();
+    assert_eq!(content, "Paris");
+
+    mock.assert();
+}
+
+#[test]
+fn test_blocking_http_error() {
+    let mut server = mockito::Server::new();
+    let mock = server
+        .mock("GET", "/v1/models")
+        .with_status(500)
+        .with_body(r#"{"error":{"message":"model not loaded"}}"#)
+        .create();
+
+    let client = Client::new(server.url()).unwrap();
+    match client.models() {
+        Err(LlamaEdgeError::Http {
+            status, message, ..
+        }) => {
+            assert_eq!(status.as_u16(), 500);
+            assert_eq!(message, "model not loaded");
+        }
+        other => panic!("unexpected result: {:?}", other),
+    }
+
+    mock.assert();
+}
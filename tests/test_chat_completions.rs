@@ -1,6 +1,6 @@
 use endpoints::chat::{
-    ChatCompletionChunk, ChatCompletionRequestMessage, ChatCompletionSystemMessage,
-    ChatCompletionUserMessage, ChatCompletionUserMessageContent,
+    ChatCompletionRequestMessage, ChatCompletionSystemMessage, ChatCompletionUserMessage,
+    ChatCompletionUserMessageContent,
 };
 use futures::StreamExt;
 use llamaedge::{params::ChatParams, Client};
@@ -55,29 +55,11 @@ async fn test_chat_stream() {
     // iterate over the stream
     let mut output = String::new();
     while let Some(item) = stream.next().await {
-        if let Ok(event) = item {
-            let event_parts = event
-                .split("data: ")
-                .map(|s| s.trim())
-                .filter(|s| !s.is_empty())
-                .collect::<Vec<&str>>();
-
-            for part in event_parts.iter() {
-                if *part == "[DONE]" {
-                    break;
-                }
-
-                if let Ok(chunk) = serde_json::from_str::<ChatCompletionChunk>(part) {
-                    if !chunk.choices.is_empty() {
-                        if let Some(content) = &chunk.choices[0].delta.content {
-                            let content = content.trim();
-                            if !content.is_empty() {
-                                // append content to output
-                                output.push_str(content);
-                            }
-                        }
-                    }
-                }
+        let chunk = item.unwrap();
+        if !chunk.choices.is_empty() {
+            if let Some(content) = &chunk.choices[0].delta.content {
+                // append content to output
+                output.push_str(content);
             }
         }
     }
@@ -86,3 +68,31 @@ async fn test_chat_stream() {
     assert!(output.contains("Paris"));
     println!("output: {}", output);
 }
+
+#[tokio::test]
+async fn test_chat_completion() {
+    let client = Client::new(SERVER_BASE_URL).unwrap();
+
+    let mut messages = Vec::new();
+    let system_message = ChatCompletionRequestMessage::System(ChatCompletionSystemMessage::new(
+        "You are a helpful assistant. Answer questions as concisely and accurately as possible.",
+        None,
+    ));
+    messages.push(system_message);
+    let user_message = ChatCompletionRequestMessage::User(ChatCompletionUserMessage::new(
+        ChatCompletionUserMessageContent::Text("What is the capital of France?".to_string()),
+        None,
+    ));
+    messages.push(user_message);
+
+    let result = client
+        .chat_completion(&messages[..], &ChatParams::default())
+        .await;
+    assert!(result.is_ok());
+
+    let chat_completion = result.unwrap();
+    assert!(!chat_completion.choices.is_empty());
+    assert!(chat_completion.usage.total_tokens > 0);
+    let content = chat_completion.choices[0].message.content.as_ref().unwrap();
+    assert!(content.contains("Paris"));
+}
@@ -0,0 +1,159 @@
+#![cfg(feature = "rag")]
+
+use endpoints::embeddings::ChunksResponse;
+use llamaedge::{
+    error::LlamaEdgeError,
+    rag::chunk::{approximate_tokens, chunk_file, split_text, ChunkOptions, Splitter, TextChunk},
+};
+
+/// Check that each chunk is the part of the source text at its offsets.
+fn assert_offsets(text: &str, chunks: &[TextChunk]) {
+    for chunk in chunks {
+        assert_eq!(&text[chunk.start..chunk.end], chunk.text);
+    }
+}
+
+fn texts(chunks: &[TextChunk]) -> Vec<&str> {
+    chunks.iter().map(|chunk| chunk.text.as_str()).collect()
+}
+
+#[test]
+fn test_split_characters_with_overlap() {
+    let text = "abcdefghij";
+    let options = ChunkOptions::new().with_capacity(4).with_overlap(1);
+    let chunks = split_text(text, &options).unwrap();
+
+    assert_eq!(texts(&chunks), vec!["abcd", "defg", "ghij"]);
+    assert_eq!((chunks[1].start, chunks[1].end), (3, 7));
+    assert_offsets(text, &chunks);
+
+    // offsets are byte offsets, even for multibyte characters
+    let text = "héllo wörld";
+    let chunks = split_text(text, &ChunkOptions::new().with_capacity(6)).unwrap();
+    assert_eq!(texts(&chunks), vec!["héllo", "wörld"]);
+    assert_offsets(text, &chunks);
+}
+
+#[test]
+fn test_split_tokens() {
+    assert_eq!(approximate_tokens("The capital of France"), 6);
+    assert_eq!(approximate_tokens("  "), 0);
+
+    let text = "The capital of France is Paris, a city of light.";
+    let options = ChunkOptions::new()
+        .with_splitter(Splitter::Tokens)
+        .with_capacity(6)
+        .with_overlap(3);
+    let chunks = split_text(text, &options).unwrap();
+
+    assert_eq!(
+        texts(&chunks),
+        vec![
+            "The capital of France",
+            "of France is Paris,",
+            "is Paris, a city of",
+            "a city of light."
+        ]
+    );
+    for chunk in &chunks {
+        assert!(approximate_tokens(&chunk.text) <= 6, "{}", chunk.text);
+    }
+    assert_offsets(text, &chunks);
+
+    // a word longer than the capacity is cut into pieces of one token
+    let options = ChunkOptions::new()
+        .with_splitter(Splitter::Tokens)
+        .with_capacity(3);
+    let chunks = split_text("internationalization", &options).unwrap();
+    assert_eq!(texts(&chunks), vec!["internationa", "lization"]);
+}
+
+#[test]
+fn test_split_sentences() {
+    let text =
+        "Paris is in France. Berlin is in Germany? \"Yes!\" Rome is in Italy.\n\nA new paragraph";
+    let options = ChunkOptions::new()
+        .with_splitter(Splitter::Sentences)
+        .with_capacity(40)
+        .with_overlap(20);
+    let chunks = split_text(text, &options).unwrap();
+
+    assert_eq!(
+        texts(&chunks),
+        vec![
+            "Paris is in France.",
+            "Berlin is in Germany? \"Yes!\"",
+            "\"Yes!\" Rome is in Italy.",
+            "Rome is in Italy.\n\nA new paragraph",
+        ]
+    );
+    assert_offsets(text, &chunks);
+}
+
+#[test]
+fn test_split_markdown() {
+    let text = "Intro.\n\n# Paris\n\nParis is in France.\n\n```sh\n# not a heading\n```\n\n## Rome\nRome is in Italy. It is old.\n";
+    let options = ChunkOptions::new()
+        .with_splitter(Splitter::Markdown)
+        .with_capacity(100)
+        .with_overlap(10);
+    let chunks = split_text(text, &options).unwrap();
+
+    assert_eq!(
+        texts(&chunks),
+        vec![
+            "Intro.",
+            "# Paris\n\nParis is in France.\n\n```sh\n# not a heading\n```",
+            "## Rome\nRome is in Italy. It is old.",
+        ]
+    );
+    assert_offsets(text, &chunks);
+
+    // long sections are split between sentences, and code blocks are kept with their section
+    let chunks = split_text(text, &options.with_capacity(30)).unwrap();
+    assert_eq!(
+        texts(&chunks),
+        vec![
+            "Intro.",
+            "# Paris\n\nParis is in France.",
+            "```sh\n# not a heading\n```",
+            "## Rome\nRome is in Italy.",
+            "It is old.",
+        ]
+    );
+    assert_offsets(text, &chunks);
+}
+
+#[test]
+fn test_chunk_file() {
+    let chunks = chunk_file(
+        "tests/assets/paris.txt",
+        &ChunkOptions::new().with_splitter(Splitter::Sentences),
+    )
+    .unwrap();
+    assert_eq!(chunks.filename, "paris.txt");
+    assert!(!chunks.chunks.is_empty());
+    assert!(chunks
+        .chunks
+        .iter()
+        .all(|chunk| chunk.text.chars().count() <= 1024));
+
+    let texts = chunks.texts();
+    let response = ChunksResponse::from(chunks);
+    assert_eq!(response.filename, "paris.txt");
+    assert_eq!(response.chunks, texts);
+
+    let result = chunk_file("tests/assets/missing.txt", &ChunkOptions::default());
+    assert!(matches!(result, Err(LlamaEdgeError::Operation(_))));
+}
+
+#[test]
+fn test_invalid_chunk_options() {
+    for options in [
+        ChunkOptions::new().with_capacity(0),
+        ChunkOptions::new().with_capacity(10).with_overlap(10),
+    ] {
+        let result = split_text("text", &options);
+        assert!(matches!(result, Err(LlamaEdgeError::InvalidArgument(_))));
+    }
+}
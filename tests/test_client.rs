@@ -0,0 +1,130 @@
+use llamaedge::{endpoint::Endpoint, error::LlamaEdgeError, Client};
+use std::time::Duration;
+
+const SERVER_BASE_URL: &str = "http://localhost:8080";
+
+#[test]
+fn test_client_builder() {
+    let result = Client::builder(SERVER_BASE_URL)
+        .with_connect_timeout(Duration::from_secs(5))
+        .with_read_timeout(Duration::from_secs(60))
+        .with_user_agent("my-service/1.0")
+        .with_default_header("x-request-source", "test")
+        .build();
+    assert!(result.is_ok());
+
+    let client = result.unwrap();
+    assert_eq!(client.server_base_url().as_str(), "http://localhost:8080/");
+}
+
+#[tokio::test]
+async fn test_client_builder_sends_configured_headers() {
+    let mut server = mockito::Server::new_async().await;
+    let mock = server
+        .mock("GET", "/v1/models")
+        .match_header("user-agent", "my-service/1.0")
+        .match_header("x-request-source", "test")
+        .with_status(200)
+        .with_body(r#"{"object":"list","data":[{"id":"llama","created":1,"object":"model","owned_by":"Not specified"}]}"#)
+        .create_async()
+        .await;
+
+    let client = Client::builder(server.url())
+        .with_user_agent("my-service/1.0")
+        .with_default_header("x-request-source", "test")
+        .build()
+        .unwrap();
+    let models = client.models().await.unwrap();
+
+    assert_eq!(models.len(), 1);
+    mock.assert_async().await;
+}
+
+#[test]
+fn test_client_builder_with_http_client() {
+    let http_client = reqwest::Client::builder()
+        .timeout(Duration::from_secs(30))
+        .build()
+        .unwrap();
+
+    let result = Client::builder(SERVER_BASE_URL)
+        .with_http_client(http_client)
+        .build();
+    assert!(result.is_ok());
+}
+
+#[test]
+fn test_client_builder_invalid_header() {
+    let result = Client::builder(SERVER_BASE_URL)
+        .with_default_header("invalid header", "value")
+        .build();
+    assert!(matches!(result, Err(LlamaEdgeError::InvalidArgument(_))));
+
+    let result = Client::builder(SERVER_BASE_URL)
+        .with_default_header("x-header", "invalid\nvalue")
+        .build();
+    assert!(matches!(result, Err(LlamaEdgeError::InvalidArgument(_))));
+}
+
+#[test]
+fn test_client_invalid_url() {
+    let result = Client::new("not a url");
+    assert!(matches!(result, Err(LlamaEdgeError::UrlParse(_))));
+}
+
+#[test]
+fn test_endpoint_url_keeps_base_path() {
+    let client = Client::new("https://gateway.internal/llamaedge/").unwrap();
+    let url = client.endpoint_url(Endpoint::ChatCompletions).unwrap();
+    assert_eq!(
+        url.as_str(),
+        "https://gateway.internal/llamaedge/v1/chat/completions"
+    );
+
+    let client = Client::new("https://gateway.internal/llamaedge").unwrap();
+    let url = client.endpoint_url(Endpoint::Models).unwrap();
+    assert_eq!(url.as_str(), "https://gateway.internal/llamaedge/v1/models");
+}
+
+#[test]
+fn test_endpoint_url_with_api_version() {
+    let client = Client::builder("https://gateway.internal/llamaedge")
+        .with_api_version("v2")
+        .build()
+        .unwrap();
+    let url = client.endpoint_url(Endpoint::Embeddings).unwrap();
+    assert_eq!(
+        url.as_str(),
+        "https://gateway.internal/llamaedge/v2/embeddings"
+    );
+
+    let client = Client::builder(SERVER_BASE_URL)
+        .with_api_version("")
+        .build()
+        .unwrap();
+    let url = client.endpoint_url(Endpoint::Embeddings).unwrap();
+    assert_eq!(url.as_str(), "http://localhost:8080/embeddings");
+}
+
+#[test]
+fn test_client_invalid_base_url() {
+    let result = Client::new("http://localhost:8080/?key=value");
+    assert!(matches!(result, Err(LlamaEdgeError::InvalidArgument(_))));
+}
+
+#[tokio::test]
+async fn test_request_with_base_path() {
+    let mut server = mockito::Server::new_async().await;
+    let mock = server
+        .mock("GET", "/llamaedge/v1/models")
+        .with_status(200)
+        .with_body(r#"{"object":"list","data":[]}"#)
+        .create_async()
+        .await;
+
+    let client = Client::new(format!("{}/llamaedge/", server.url())).unwrap();
+    let result = client.models().await;
+    assert!(result.is_ok());
+
+    mock.assert_async().await;
+}
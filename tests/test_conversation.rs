@@ -0,0 +1,262 @@
+use endpoints::chat::{
+    ChatCompletionAssistantMessage, ChatCompletionRequestMessage, ChatCompletionUserMessage,
+    ChatCompletionUserMessageContent, Function, ToolCall,
+};
+use futures::StreamExt;
+use llamaedge::{
+    conversation::{Conversation, HistoryTrim},
+    params::ChatParams,
+    Client,
+};
+
+const ANSWER_RESPONSE: &str = r#"{"id":"chatcmpl-1","object":"chat.completion","created":1,"model":"llama","choices":[{"index":0,"message":{"content":"Paris.","tool_calls":[],"role":"assistant"},"finish_reason":"stop","logprobs":null}],"usage":{"prompt_tokens":10,"completion_tokens":2,"total_tokens":12}}"#;
+
+fn user(text: &str) -> ChatCompletionRequestMessage {
+    ChatCompletionRequestMessage::User(ChatCompletionUserMessage::new(
+        ChatCompletionUserMessageContent::Text(text.to_string()),
+        None,
+    ))
+}
+
+fn assistant(text: &str) -> ChatCompletionRequestMessage {
+    ChatCompletionRequestMessage::Assistant(ChatCompletionAssistantMessage::new(
+        Some(text.to_string()),
+        None,
+        None,
+    ))
+}
+
+#[tokio::test]
+async fn test_conversation_chat() {
+    let mut server = mockito::Server::new_async().await;
+    server
+        .mock("POST", "/v1/chat/completions")
+        .with_status(200)
+        .with_body(ANSWER_RESPONSE)
+        .create_async()
+        .await;
+
+    let client = Client::new(server.url()).unwrap();
+    let mut conversation = Conversation::new().with_system_prompt("You are a helpful assistant.");
+    let result = conversation
+        .chat(
+            &client,
+            "What is the capital of France?",
+            &ChatParams::default(),
+        )
+        .await;
+    assert!(result.is_ok());
+
+    assert_eq!(conversation.history().len(), 2);
+    assert_eq!(conversation.history()[1], assistant("Paris."));
+    // system prompt followed by the history
+    assert_eq!(conversation.messages().len(), 3);
+}
+
+#[tokio::test]
+async fn test_conversation_chat_error_keeps_history() {
+    let mut server = mockito::Server::new_async().await;
+    server
+        .mock("POST", "/v1/chat/completions")
+        .with_status(500)
+        .with_body("internal error")
+        .create_async()
+        .await;
+
+    let client = Client::new(server.url()).unwrap();
+    let mut conversation = Conversation::new();
+    let result = conversation
+        .chat(
+            &client,
+            "What is the capital of France?",
+            &ChatParams::default(),
+        )
+        .await;
+    assert!(result.is_err());
+    assert!(conversation.history().is_empty());
+}
+
+#[tokio::test]
+async fn test_conversation_chat_stream() {
+    let chunk = |content: &str| {
+        format!(
+            r#"data: {{"id":"chatcmpl-1","choices":[{{"index":0,"delta":{{"content":"{}","role":"assistant"}},"logprobs":null,"finish_reason":null}}],"created":1,"model":"llama","system_fingerprint":"fp","object":"chat.completion.chunk"}}"#,
+            content
+        ) + "\n\n"
+    };
+    let body = chunk("Par") + &chunk("is.") + "data: [DONE]\n\n";
+
+    let mut server = mockito::Server::new_async().await;
+    server
+        .mock("POST", "/v1/chat/completions")
+        .with_status(200)
+        .with_header("content-type", "text/event-stream")
+        .with_body(body)
+        .create_async()
+        .await;
+
+    let client = Client::new(server.url()).unwrap();
+    let mut conversation = Conversation::new();
+    let mut stream = conversation
+        .chat_stream(
+            &client,
+            "What is the capital of France?",
+            &ChatParams::default(),
+        )
+        .await
+        .unwrap();
+    while let Some(chunk) = stream.next().await {
+        assert!(chunk.is_ok());
+    }
+    drop(stream);
+
+    assert_eq!(conversation.history().len(), 2);
+    assert_eq!(conversation.history()[1], assistant("Paris."));
+}
+
+#[tokio::test]
+async fn test_conversation_chat_stream_tool_calls() {
+    let chunk = |tool_call: &str| {
+        format!(
+            r#"data: {{"id":"chatcmpl-1","choices":[{{"index":0,"delta":{{"tool_calls":[{}],"role":"assistant"}},"logprobs":null,"finish_reason":null}}],"created":1,"model":"llama","system_fingerprint":"fp","object":"chat.completion.chunk"}}"#,
+            tool_call
+        ) + "\n\n"
+    };
+    // the arguments of the first call arrive in two pieces, after the start of the second call
+    let body = chunk(
+        r#"{"index":0,"id":"call_1","type":"function","function":{"name":"get_weather","arguments":"{\"city\":"}}"#,
+    ) + &chunk(
+        r#"{"index":1,"id":"call_2","type":"function","function":{"name":"get_time","arguments":"{}"}}"#,
+    ) + &chunk(
+        r#"{"index":0,"id":"","type":"","function":{"name":"","arguments":"\"Paris\"}"}}"#,
+    ) + "data: [DONE]\n\n";
+
+    let mut server = mockito::Server::new_async().await;
+    server
+        .mock("POST", "/v1/chat/completions")
+        .with_status(200)
+        .with_header("content-type", "text/event-stream")
+        .with_body(body)
+        .create_async()
+        .await;
+
+    let client = Client::new(server.url()).unwrap();
+    let mut conversation = Conversation::new();
+    let mut stream = conversation
+        .chat_stream(
+            &client,
+            "What is the weather in Paris?",
+            &ChatParams::default(),
+        )
+        .await
+        .unwrap();
+    while let Some(chunk) = stream.next().await {
+        assert!(chunk.is_ok());
+    }
+    drop(stream);
+
+    let tool_call = |id: &str, name: &str, arguments: &str| ToolCall {
+        id: id.to_string(),
+        ty: "function".to_string(),
+        function: Function {
+            name: name.to_string(),
+            arguments: arguments.to_string(),
+        },
+    };
+    assert_eq!(conversation.history().len(), 2);
+    assert_eq!(
+        conversation.history()[1],
+        ChatCompletionRequestMessage::Assistant(ChatCompletionAssistantMessage::new(
+            None,
+            None,
+            Some(vec![
+                tool_call("call_1", "get_weather", r#"{"city":"Paris"}"#),
+                tool_call("call_2", "get_time", "{}"),
+            ]),
+        ))
+    );
+}
+
+#[tokio::test]
+async fn test_conversation_chat_stream_dropped() {
+    let chunk = r#"data: {"id":"chatcmpl-1","choices":[{"index":0,"delta":{"content":"Par","role":"assistant"},"logprobs":null,"finish_reason":null}],"created":1,"model":"llama","system_fingerprint":"fp","object":"chat.completion.chunk"}"#;
+
+    let mut server = mockito::Server::new_async().await;
+    server
+        .mock("POST", "/v1/chat/completions")
+        .with_status(200)
+        .with_header("content-type", "text/event-stream")
+        .with_body(format!("{}\n\n{}\n\ndata: [DONE]\n\n", chunk, chunk))
+        .create_async()
+        .await;
+
+    let client = Client::new(server.url()).unwrap();
+    let mut conversation = Conversation::new();
+    conversation.push(user("What is the capital of Germany?"));
+    conversation.push(assistant("Berlin."));
+    let messages = conversation.messages();
+
+    let mut stream = conversation
+        .chat_stream(
+            &client,
+            "What is the capital of France?",
+            &ChatParams::default(),
+        )
+        .await
+        .unwrap();
+    assert!(stream.next().await.unwrap().is_ok());
+    drop(stream);
+
+    assert_eq!(conversation.messages(), messages);
+}
+
+#[test]
+fn test_conversation_trim_last_turns() {
+    let mut conversation = Conversation::new().with_history_trim(HistoryTrim::LastTurns(2));
+    for i in 0..5 {
+        conversation.push(user(&format!("question {}", i)));
+        conversation.push(assistant(&format!("answer {}", i)));
+    }
+
+    assert_eq!(conversation.history().len(), 4);
+    assert_eq!(conversation.history()[0], user("question 3"));
+}
+
+#[test]
+fn test_conversation_trim_max_chars() {
+    let mut conversation = Conversation::new().with_history_trim(HistoryTrim::MaxChars(25));
+    for i in 0..5 {
+        // each turn has 20 characters
+        conversation.push(user(&format!("question {}", i)));
+        conversation.push(assistant(&format!("answer {}..", i)));
+    }
+
+    assert_eq!(conversation.history().len(), 2);
+    assert_eq!(conversation.history()[0], user("question 4"));
+
+    // the system prompt counts towards the budget
+    let mut conversation = Conversation::new()
+        .with_system_prompt("Be brief.")
+        .with_history_trim(HistoryTrim::MaxChars(45));
+    for i in 0..3 {
+        conversation.push(user(&format!("question {}", i)));
+        conversation.push(assistant(&format!("answer {}..", i)));
+    }
+
+    assert_eq!(conversation.history().len(), 2);
+    assert_eq!(conversation.history()[0], user("question 2"));
+}
+
+#[test]
+fn test_conversation_json_round_trip() {
+    let mut conversation = Conversation::new()
+        .with_system_prompt("You are a helpful assistant.")
+        .with_history_trim(HistoryTrim::LastTurns(10));
+    conversation.push(user("What is the capital of France?"));
+    conversation.push(assistant("Paris."));
+
+    let json = conversation.to_json().unwrap();
+    let restored = Conversation::from_json(json).unwrap();
+    assert_eq!(restored.system_prompt(), conversation.system_prompt());
+    assert_eq!(restored.history(), conversation.history());
+}
@@ -15,5 +15,5 @@ async fn test_embeddings() {
 
     let embeddings = result.unwrap();
     println!("length of embeddings: {}", embeddings.data.len());
-    assert!(embeddings.data.len() > 0);
+    assert!(!embeddings.data.is_empty());
 }
@@ -0,0 +1,131 @@
+use llamaedge::{error::LlamaEdgeError, Client};
+use reqwest::StatusCode;
+use std::time::Duration;
+
+#[tokio::test]
+async fn test_http_error_with_json_body() {
+    let mut server = mockito::Server::new_async().await;
+    let mock = server
+        .mock("GET", "/v1/models")
+        .with_status(500)
+        .with_body(r#"{"error":{"message":"Failed to load the model"}}"#)
+        .create_async()
+        .await;
+
+    let client = Client::new(server.url()).unwrap();
+    let err = client.models().await.unwrap_err();
+    match &err {
+        LlamaEdgeError::Http {
+            status,
+            message,
+            endpoint,
+        } => {
+            assert_eq!(*status, StatusCode::INTERNAL_SERVER_ERROR);
+            assert_eq!(message, "Failed to load the model");
+            assert_eq!(endpoint, "/v1/models");
+        }
+        _ => panic!("unexpected error: {:?}", err),
+    }
+    assert!(!err.is_retryable());
+
+    mock.assert_async().await;
+}
+
+#[tokio::test]
+async fn test_http_error_message_fallbacks() {
+    let mut server = mockito::Server::new_async().await;
+    let client = Client::new(server.url()).unwrap();
+
+    // an `error` field that is not a string does not hide the other fields
+    for (body, expected) in [
+        (
+            r#"{"error":{"code":500},"message":"Failed to load the model"}"#,
+            "Failed to load the model",
+        ),
+        (
+            r#"{"error":500,"status":{"error":"Collection not found"}}"#,
+            "Collection not found",
+        ),
+        (r#"{"error":{"code":500}}"#, r#"{"error":{"code":500}}"#),
+    ] {
+        let mock = server
+            .mock("GET", "/v1/models")
+            .with_status(500)
+            .with_body(body)
+            .create_async()
+            .await;
+
+        let err = client.models().await.unwrap_err();
+        match &err {
+            LlamaEdgeError::Http { message, .. } => assert_eq!(message, expected),
+            _ => panic!("unexpected error: {:?}", err),
+        }
+
+        mock.remove_async().await;
+    }
+}
+
+#[tokio::test]
+async fn test_http_error_with_text_body() {
+    let mut server = mockito::Server::new_async().await;
+    server
+        .mock("GET", "/v1/models")
+        .with_status(503)
+        .with_body("The server is busy")
+        .create_async()
+        .await;
+
+    let client = Client::new(server.url()).unwrap();
+    let err = client.models().await.unwrap_err();
+    assert_eq!(err.status(), Some(StatusCode::SERVICE_UNAVAILABLE));
+    assert!(err.to_string().contains("The server is busy"));
+    assert!(err.is_retryable());
+}
+
+#[tokio::test]
+async fn test_decode_error_keeps_body() {
+    let mut server = mockito::Server::new_async().await;
+    server
+        .mock("GET", "/v1/models")
+        .with_status(200)
+        .with_body("not json")
+        .create_async()
+        .await;
+
+    let client = Client::new(server.url()).unwrap();
+    let err = client.models().await.unwrap_err();
+    match err {
+        LlamaEdgeError::Decode { body, .. } => assert_eq!(body, "not json"),
+        _ => panic!("unexpected error: {:?}", err),
+    }
+}
+
+#[tokio::test]
+async fn test_transport_error() {
+    // nothing listens on port 9 of the loopback interface
+    let client = Client::new("http://127.0.0.1:9").unwrap();
+    let err = client.models().await.unwrap_err();
+    assert!(matches!(err, LlamaEdgeError::Transport(_)));
+    assert!(err.is_retryable());
+}
+
+#[tokio::test]
+async fn test_timeout_error() {
+    let mut server = mockito::Server::new_async().await;
+    server
+        .mock("GET", "/v1/models")
+        .with_status(200)
+        .with_chunked_body(|w| {
+            std::thread::sleep(Duration::from_millis(500));
+            w.write_all(b"{}")
+        })
+        .create_async()
+        .await;
+
+    let client = Client::builder(server.url())
+        .with_timeout(Duration::from_millis(100))
+        .build()
+        .unwrap();
+    let err = client.models().await.unwrap_err();
+    assert!(matches!(err, LlamaEdgeError::Timeout(_)));
+}
@@ -1,4 +1,4 @@
-use llamaedge::Client;
+use llamaedge::{error::LlamaEdgeError, Client};
 
 const SERVER_BASE_URL: &str = "http://localhost:8080";
 
@@ -11,3 +11,79 @@ async fn test_upload_file() {
     let file_object = result.unwrap();
     assert_eq!(file_object.filename, "test.wav");
 }
+
+const FILE_OBJECT: &str = r#"{"id":"file_4bc24593-2a57-4646-af16-028855e7802e","bytes":13,"created_at":1733817460,"filename":"notes.txt","object":"file","purpose":"assistants"}"#;
+
+#[tokio::test]
+async fn test_list_and_retrieve_files() {
+    let mut server = mockito::Server::new_async().await;
+    let list_mock = server
+        .mock("GET", "/v1/files")
+        .with_status(200)
+        .with_body(format!(r#"{{"object":"list","data":[{}]}}"#, FILE_OBJECT))
+        .create_async()
+        .await;
+    let retrieve_mock = server
+        .mock("GET", "/v1/files/file_4bc24593-2a57-4646-af16-028855e7802e")
+        .with_status(200)
+        .with_body(FILE_OBJECT)
+        .create_async()
+        .await;
+
+    let client = Client::new(server.url()).unwrap();
+
+    let files = client.list_files().await.unwrap();
+    assert_eq!(files.len(), 1);
+    assert_eq!(files[0].filename, "notes.txt");
+
+    let file_object = client.retrieve_file(&files[0].id).await.unwrap();
+    assert_eq!(file_object.bytes, 13);
+
+    list_mock.assert_async().await;
+    retrieve_mock.assert_async().await;
+}
+
+#[tokio::test]
+async fn test_retrieve_and_download_file_content() {
+    let mut server = mockito::Server::new_async().await;
+    let mock = server
+        .mock("GET", "/v1/files/file_1/content")
+        .with_status(200)
+        .with_body("Hello, world!")
+        .expect(2)
+        .create_async()
+        .await;
+
+    let client = Client::new(server.url()).unwrap();
+
+    let content = client.retrieve_file_content("file_1").await.unwrap();
+    assert_eq!(&content[..], b"Hello, world!");
+
+    let path = std::env::temp_dir().join("llamaedge_test_download_file.txt");
+    let written = client.download_file("file_1", &path).await.unwrap();
+    assert_eq!(written, 13);
+    assert_eq!(std::fs::read(&path).unwrap(), b"Hello, world!");
+    std::fs::remove_file(&path).unwrap();
+
+    mock.assert_async().await;
+}
+
+#[tokio::test]
+async fn test_delete_file() {
+    let mut server = mockito::Server::new_async().await;
+    let mock = server
+        .mock("DELETE", "/v1/files/file_1")
+        .with_status(200)
+        .with_body(r#"{"id":"file_1","object":"file","deleted":true}"#)
+        .create_async()
+        .await;
+
+    let client = Client::new(server.url()).unwrap();
+    let status = client.delete_file("file_1").await.unwrap();
+    assert!(status.deleted);
+
+    let result = client.delete_file("").await;
+    assert!(matches!(result, Err(LlamaEdgeError::InvalidArgument(_))));
+
+    mock.assert_async().await;
+}
@@ -14,6 +14,6 @@ mod tests {
         assert!(result.is_ok());
 
         let image = result.unwrap();
-        assert!(image.len() > 0);
+        assert!(!image.is_empty());
     }
 }
@@ -0,0 +1,141 @@
+#![cfg(feature = "image")]
+
+use llamaedge::{
+    error::LlamaEdgeError,
+    image::{ImageData, ImageFormat},
+    params::ImageCreateParams,
+    Client,
+};
+
+/// The signature of a PNG file followed by the start of its header chunk.
+const PNG: &[u8] = b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR";
+
+/// `PNG`, base64-encoded.
+const PNG_BASE64: &str = "iVBORw0KGgoAAAANSUhEUg==";
+
+/// Mock the image generation endpoint returning the given image objects.
+async fn mock_create_image(server: &mut mockito::Server, images: &str) -> mockito::Mock {
+    server
+        .mock("POST", "/v1/images/generations")
+        .with_status(200)
+        .with_body(format!(r#"{{"created":1,"data":[{}]}}"#, images))
+        .create_async()
+        .await
+}
+
+#[test]
+fn test_image_format_detect() {
+    assert_eq!(ImageFormat::detect(PNG), Some(ImageFormat::Png));
+    assert_eq!(
+        ImageFormat::detect(b"\xFF\xD8\xFF\xE0\0\x10JFIF"),
+        Some(ImageFormat::Jpeg)
+    );
+    assert_eq!(
+        ImageFormat::detect(b"RIFF\x24\0\0\0WEBPVP8 "),
+        Some(ImageFormat::Webp)
+    );
+    assert_eq!(ImageFormat::detect(b"RIFF\x24\0\0\0WAVEfmt "), None);
+    assert_eq!(ImageFormat::detect(b""), None);
+
+    assert_eq!(ImageFormat::Jpeg.mime_type(), "image/jpeg");
+    assert_eq!(ImageFormat::Webp.extension(), "webp");
+    assert_eq!(ImageData::new(PNG).format(), Some(ImageFormat::Png));
+}
+
+#[tokio::test]
+async fn test_image_bytes_from_b64_json() {
+    let mut server = mockito::Server::new_async().await;
+    let _mock = mock_create_image(
+        &mut server,
+        &format!(
+            r#"{{"b64_json":"{}","prompt":"A lovely dog"}},{{"url":"data:image/png;base64,{}","prompt":"A lovely dog"}}"#,
+            PNG_BASE64, PNG_BASE64
+        ),
+    )
+    .await;
+
+    let client = Client::new(server.url()).unwrap();
+    let images = client
+        .create_image("A lovely dog", ImageCreateParams::default())
+        .await
+        .unwrap();
+    assert_eq!(images.len(), 2);
+
+    for image in &images {
+        let image_data = client.image_bytes(image).await.unwrap();
+        assert_eq!(image_data.bytes().as_ref(), PNG);
+        assert_eq!(image_data.format(), Some(ImageFormat::Png));
+    }
+}
+
+#[tokio::test]
+async fn test_image_bytes_from_url_and_save() {
+    let mut server = mockito::Server::new_async().await;
+    let _create_mock = mock_create_image(
+        &mut server,
+        r#"{"url":"/images/dog.png","prompt":"A lovely dog"}"#,
+    )
+    .await;
+    // the image is on the same origin as the server, so it is fetched with the credentials of the client
+    let download_mock = server
+        .mock("GET", "/images/dog.png")
+        .match_header("authorization", "Bearer secret-key")
+        .with_status(200)
+        .with_body(PNG)
+        .expect(2)
+        .create_async()
+        .await;
+
+    let client = Client::builder(server.url())
+        .with_api_key("secret-key")
+        .build()
+        .unwrap();
+    let images = client
+        .create_image("A lovely dog", ImageCreateParams::default())
+        .await
+        .unwrap();
+
+    let image_data = client.image_bytes(&images[0]).await.unwrap();
+    assert_eq!(image_data.bytes().as_ref(), PNG);
+
+    let path = std::env::temp_dir().join(format!("llamaedge-image-{}.png", std::process::id()));
+    let image_data = client.save_image(&images[0], &path).await.unwrap();
+    assert_eq!(image_data.format(), Some(ImageFormat::Png));
+    assert_eq!(std::fs::read(&path).unwrap(), PNG);
+    std::fs::remove_file(&path).unwrap();
+
+    download_mock.assert_async().await;
+}
+
+#[tokio::test]
+async fn test_image_bytes_errors() {
+    let mut server = mockito::Server::new_async().await;
+    let _create_mock = mock_create_image(
+        &mut server,
+        r#"{"b64_json":"not base64!","prompt":"A lovely dog"},{"prompt":"A lovely dog"},{"url":"/images/missing.png","prompt":"A lovely dog"}"#,
+    )
+    .await;
+    let _download_mock = server
+        .mock("GET", "/images/missing.png")
+        .with_status(404)
+        .with_body("Not Found")
+        .create_async()
+        .await;
+
+    let client = Client::new(server.url()).unwrap();
+    let images = client
+        .create_image("A lovely dog", ImageCreateParams::default())
+        .await
+        .unwrap();
+
+    assert!(matches!(
+        client.image_bytes(&images[0]).await,
+        Err(LlamaEdgeError::Decode { .. })
+    ));
+    assert!(matches!(
+        client.image_bytes(&images[1]).await,
+        Err(LlamaEdgeError::InvalidArgument(_))
+    ));
+    let error = client.image_bytes(&images[2]).await.unwrap_err();
+    assert_eq!(error.status(), Some(reqwest::StatusCode::NOT_FOUND));
+}
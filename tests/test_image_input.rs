@@ -0,0 +1,206 @@
+#![cfg(feature = "image")]
+
+use llamaedge::{
+    error::LlamaEdgeError,
+    image::{image_dimensions, ImageInput},
+    params::{ImageCreateParams, ImageEditParams},
+    upload::UploadFile,
+    Client,
+};
+use mockito::Matcher;
+
+/// Build the header of a PNG image with the given dimensions.
+fn png(width: u32, height: u32) -> Vec<u8> {
+    let mut bytes = b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR".to_vec();
+    bytes.extend_from_slice(&width.to_be_bytes());
+    bytes.extend_from_slice(&height.to_be_bytes());
+    bytes.extend_from_slice(&[8, 6, 0, 0, 0]);
+    bytes
+}
+
+#[test]
+fn test_image_dimensions() {
+    assert_eq!(image_dimensions(&png(512, 256)), Some((512, 256)));
+
+    // a JFIF segment followed by a baseline start-of-frame segment
+    let jpeg = [
+        &[0xFF, 0xD8, 0xFF, 0xE0, 0x00, 0x04, 0x4A, 0x46][..],
+        &[0xFF, 0xC0, 0x00, 0x11, 0x08, 0x01, 0x2C, 0x02, 0x80, 0x03],
+    ]
+    .concat();
+    assert_eq!(image_dimensions(&jpeg), Some((640, 300)));
+
+    let mut vp8 = b"RIFF\0\0\0\0WEBPVP8 \0\0\0\0\x30\x01\x00\x9d\x01\x2a".to_vec();
+    vp8.extend_from_slice(&[0x00, 0x02, 0x00, 0x01]);
+    assert_eq!(image_dimensions(&vp8), Some((512, 256)));
+
+    let mut vp8l = b"RIFF\0\0\0\0WEBPVP8L\0\0\0\0\x2f".to_vec();
+    vp8l.extend_from_slice(&(511u32 | (255 << 14)).to_le_bytes());
+    assert_eq!(image_dimensions(&vp8l), Some((512, 256)));
+
+    let mut vp8x = b"RIFF\0\0\0\0WEBPVP8X\0\0\0\0\0\0\0\0".to_vec();
+    vp8x.extend_from_slice(&[0xFF, 0x01, 0x00, 0xFF, 0x00, 0x00]);
+    assert_eq!(image_dimensions(&vp8x), Some((512, 256)));
+
+    assert_eq!(image_dimensions(b"GIF89a"), None);
+    assert_eq!(image_dimensions(&png(512, 256)[..20]), None);
+}
+
+#[tokio::test]
+async fn test_edit_image_mask_dimensions_mismatch() {
+    let mut server = mockito::Server::new_async().await;
+    let mock = server
+        .mock("POST", "/v1/images/edits")
+        .expect(0)
+        .create_async()
+        .await;
+
+    let client = Client::new(server.url()).unwrap();
+    let params = ImageEditParams {
+        mask: Some(ImageInput::from_bytes(png(256, 256), "mask.png")),
+        ..Default::default()
+    };
+    let result = client
+        .edit_image(
+            ImageInput::from_bytes(png(512, 512), "dog.png"),
+            "A lovely cat",
+            params,
+        )
+        .await;
+
+    match result {
+        Err(LlamaEdgeError::InvalidArgument(message)) => {
+            assert!(message.contains("256x256"), "{}", message);
+            assert!(message.contains("512x512"), "{}", message);
+        }
+        other => panic!("unexpected result: {:?}", other),
+    }
+    mock.assert_async().await;
+}
+
+#[tokio::test]
+async fn test_edit_image_from_uploaded_file() {
+    let mut server = mockito::Server::new_async().await;
+    let _retrieve_mock = server
+        .mock("GET", "/v1/files/file_1")
+        .with_status(200)
+        .with_body(r#"{"id":"file_1","bytes":33,"created_at":1,"filename":"dog.png","object":"file","purpose":"assistants"}"#)
+        .create_async()
+        .await;
+    let content_mock = server
+        .mock("GET", "/v1/files/file_1/content")
+        .with_status(200)
+        .with_body(png(512, 512))
+        .create_async()
+        .await;
+    let edit_mock = server
+        .mock("POST", "/v1/images/edits")
+        .match_body(Matcher::AllOf(vec![
+            Matcher::Regex(r#"name="file"; filename="dog.png""#.to_string()),
+            Matcher::Regex(r#"name="mask"; filename="mask.png""#.to_string()),
+            Matcher::Regex("name=\"prompt\"\r\n(.*\r\n)*\r\nA lovely cat\r\n".to_string()),
+            Matcher::Regex("name=\"steps\"\r\n(.*\r\n)*\r\n20\r\n".to_string()),
+        ]))
+        .with_status(200)
+        .with_body(r#"{"created":1,"data":[{"url":"/images/cat.png","prompt":"A lovely cat"}]}"#)
+        .create_async()
+        .await;
+
+    let client = Client::new(server.url()).unwrap();
+    let file = client.retrieve_file("file_1").await.unwrap();
+    let params = ImageEditParams {
+        mask: Some(ImageInput::from_bytes(png(512, 512), "mask.png")),
+        ..Default::default()
+    };
+    let images = client
+        .edit_image(file, "A lovely cat", params)
+        .await
+        .unwrap();
+
+    assert_eq!(images.len(), 1);
+    content_mock.assert_async().await;
+    edit_mock.assert_async().await;
+}
+
+#[tokio::test]
+async fn test_edit_image_from_reader() {
+    let mut server = mockito::Server::new_async().await;
+    let streamed_mock = server
+        .mock("POST", "/v1/images/edits")
+        .match_body(Matcher::AllOf(vec![
+            Matcher::Regex(r#"filename="dog"\r\nContent-Type: image/png"#.to_string()),
+            Matcher::Regex("raw image content".to_string()),
+        ]))
+        .with_status(200)
+        .with_body(r#"{"created":1,"data":[{"url":"/images/cat.png","prompt":"A lovely cat"}]}"#)
+        .create_async()
+        .await;
+    let buffered_mock = server
+        .mock("POST", "/v1/images/edits")
+        .match_body(Matcher::AllOf(vec![
+            Matcher::Regex(r#"filename="cat"\r\nContent-Type: image/x-cat"#.to_string()),
+            Matcher::Regex(r#"name="mask"; filename="mask.png""#.to_string()),
+        ]))
+        .with_status(200)
+        .with_body(r#"{"created":1,"data":[{"url":"/images/cat.png","prompt":"A lovely cat"}]}"#)
+        .create_async()
+        .await;
+
+    let client = Client::new(server.url()).unwrap();
+
+    // the explicit content type is sent rather than the detected one
+    let reader = std::io::Cursor::new(b"raw image content".to_vec());
+    let image = UploadFile::from_reader(reader, "dog").with_mime_type("image/png");
+    let images = client
+        .edit_image(image, "A lovely cat", ImageEditParams::default())
+        .await
+        .unwrap();
+    assert_eq!(images.len(), 1);
+
+    // the content type is kept when the image is read to check the mask
+    let reader = std::io::Cursor::new(png(512, 512));
+    let image = UploadFile::from_reader(reader, "cat").with_mime_type("image/x-cat");
+    let params = ImageEditParams {
+        mask: Some(ImageInput::from_bytes(png(512, 512), "mask.png")),
+        ..Default::default()
+    };
+    let images = client
+        .edit_image(image, "A lovely cat", params)
+        .await
+        .unwrap();
+    assert_eq!(images.len(), 1);
+
+    streamed_mock.assert_async().await;
+    buffered_mock.assert_async().await;
+}
+
+#[tokio::test]
+async fn test_create_image_uploads_control_image() {
+    let mut server = mockito::Server::new_async().await;
+    let upload_mock = server
+        .mock("POST", "/v1/files")
+        .match_body(Matcher::Regex(
+            r#"name="file"; filename="pose.png""#.to_string(),
+        ))
+        .with_status(200)
+        .with_body(r#"{"id":"file_2","bytes":33,"created_at":1,"filename":"pose.png","object":"file","purpose":"assistants"}"#)
+        .create_async()
+        .await;
+    let create_mock = server
+        .mock("POST", "/v1/images/generations")
+        .with_status(200)
+        .with_body(r#"{"created":1,"data":[{"url":"/images/dog.png","prompt":"A lovely dog"}]}"#)
+        .create_async()
+        .await;
+
+    let client = Client::new(server.url()).unwrap();
+    let params = ImageCreateParams {
+        control_image: Some(ImageInput::from_bytes(png(512, 512), "pose.png")),
+        ..Default::default()
+    };
+    let images = client.create_image("A lovely dog", params).await.unwrap();
+
+    assert_eq!(images.len(), 1);
+    upload_mock.assert_async().await;
+    create_mock.assert_async().await;
+}
@@ -0,0 +1,104 @@
+#![cfg(feature = "image")]
+
+use llamaedge::{
+    endpoint::Endpoint, error::LlamaEdgeError, image::ImageInput, params::ImageEditParams, Client,
+};
+use mockito::Matcher;
+
+/// The header of a 512x512 PNG image.
+const PNG: &[u8] = b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR\0\0\x02\0\0\0\x02\0\x08\x06\0\0\0";
+
+/// Match a text field of a multipart form.
+fn field(name: &str, value: &str) -> Matcher {
+    Matcher::Regex(format!("name=\"{}\"\r\n(.*\r\n)*\r\n{}\r\n", name, value))
+}
+
+#[tokio::test]
+async fn test_create_image_variation() {
+    let mut server = mockito::Server::new_async().await;
+    let mock = server
+        .mock("POST", "/v1/images/variations")
+        .match_body(Matcher::AllOf(vec![
+            Matcher::Regex(r#"name="file"; filename="base.png""#.to_string()),
+            field("n", "3"),
+            field("seed", "7"),
+            field("scheduler", "discrete"),
+            field("style_ratio", "0.5"),
+        ]))
+        .with_status(200)
+        .with_body(r#"{"created":1,"data":[{"url":"/images/1.png","prompt":""},{"url":"/images/2.png","prompt":""},{"url":"/images/3.png","prompt":""}]}"#)
+        .create_async()
+        .await;
+
+    let client = Client::new(server.url()).unwrap();
+    let params = ImageEditParams {
+        n: 3,
+        seed: 7,
+        style_ratio: 0.5,
+        ..Default::default()
+    };
+    let images = client
+        .create_image_variation(ImageInput::from_bytes(PNG, "base.png"), params)
+        .await
+        .unwrap();
+
+    assert_eq!(images.len(), 3);
+    mock.assert_async().await;
+}
+
+#[tokio::test]
+async fn test_image_to_image() {
+    let mut server = mockito::Server::new_async().await;
+    let mock = server
+        .mock("POST", "/v1/images/edits")
+        .match_body(Matcher::AllOf(vec![
+            Matcher::Regex(r#"name="file"; filename="base.png""#.to_string()),
+            field("prompt", "A watercolor painting"),
+            field("strength", "0.35"),
+            field("seed", "42"),
+        ]))
+        .with_status(200)
+        .with_body(
+            r#"{"created":1,"data":[{"url":"/images/1.png","prompt":"A watercolor painting"}]}"#,
+        )
+        .create_async()
+        .await;
+
+    let client = Client::new(server.url()).unwrap();
+    let images = client
+        .image_to_image(
+            ImageInput::from_bytes(PNG, "base.png"),
+            "A watercolor painting",
+            0.35,
+            ImageEditParams::default(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(images.len(), 1);
+    mock.assert_async().await;
+
+    for strength in [-0.1, 1.5, f32::NAN] {
+        let result = client
+            .image_to_image(
+                ImageInput::from_bytes(PNG, "base.png"),
+                "A watercolor painting",
+                strength,
+                ImageEditParams::default(),
+            )
+            .await;
+        assert!(matches!(result, Err(LlamaEdgeError::InvalidArgument(_))));
+    }
+}
+
+#[test]
+fn test_image_variations_endpoint() {
+    let client = Client::new("http://localhost:8080").unwrap();
+    assert_eq!(
+        client
+            .endpoint_url(Endpoint::ImageVariations)
+            .unwrap()
+            .as_str(),
+        "http://localhost:8080/v1/images/variations"
+    );
+    assert!(!Endpoint::ImageVariations.is_idempotent());
+}
@@ -0,0 +1,266 @@
+#![cfg(feature = "rag")]
+
+use llamaedge::{
+    error::LlamaEdgeError,
+    params::EmbeddingsParams,
+    rag::ingest::{IngestEvent, IngestOptions},
+    Client,
+};
+use mockito::{Matcher, Mock, ServerGuard};
+use serde_json::json;
+use std::{
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+};
+
+const EMBEDDINGS_RESPONSE: &str = r#"{"object":"list","data":[],"model":"nomic-embed","usage":{"prompt_tokens":4,"completion_tokens":0,"total_tokens":4}}"#;
+
+fn params() -> EmbeddingsParams {
+    EmbeddingsParams {
+        vdb_server_url: Some("http://localhost:6333".to_string()),
+        vdb_collection_name: Some("docs".to_string()),
+        ..Default::default()
+    }
+}
+
+/// Create a directory of documents: two text files, a nested Markdown file, and a file with an unsupported extension.
+fn documents(name: &str) -> PathBuf {
+    let root =
+        std::env::temp_dir().join(format!("llamaedge-ingest-{}-{}", name, std::process::id()));
+    let _ = std::fs::remove_dir_all(&root);
+    std::fs::create_dir_all(root.join("guides")).unwrap();
+    std::fs::write(root.join("a.txt"), "Paris is the capital of France.").unwrap();
+    std::fs::write(root.join("b.txt"), "Berlin is the capital of Germany.").unwrap();
+    std::fs::write(
+        root.join("guides").join("c.md"),
+        "# Rome\n\nRome is in Italy.",
+    )
+    .unwrap();
+    std::fs::write(root.join("image.png"), [0x89, 0x50, 0x4E, 0x47]).unwrap();
+    root
+}
+
+/// Mock the upload of a file.
+async fn mock_upload(server: &mut ServerGuard, filename: &str) -> Mock {
+    server
+        .mock("POST", "/v1/files")
+        .match_body(Matcher::Regex(format!(
+            r#"name="file"; filename="{}""#,
+            filename
+        )))
+        .with_status(200)
+        .with_body(format!(
+            r#"{{"id":"file_{0}","bytes":32,"created_at":1,"filename":"{0}","object":"file","purpose":"assistants"}}"#,
+            filename
+        ))
+        .create_async()
+        .await
+}
+
+/// Mock the upload of a file and its chunking into the given chunks.
+async fn mock_file(server: &mut ServerGuard, filename: &str, chunks: &[&str]) -> Mock {
+    mock_upload(server, filename).await;
+    server
+        .mock("POST", "/v1/chunks")
+        .match_body(Matcher::PartialJson(json!({ "filename": filename })))
+        .with_status(200)
+        .with_body(
+            json!({ "id": format!("file_{}", filename), "filename": filename, "chunks": chunks })
+                .to_string(),
+        )
+        .create_async()
+        .await
+}
+
+#[tokio::test]
+async fn test_ingest_directory() {
+    let root = documents("directory");
+    let mut server = mockito::Server::new_async().await;
+    mock_file(
+        &mut server,
+        "a.txt",
+        &["Paris is", "the capital of France."],
+    )
+    .await;
+    mock_file(&mut server, "c.md", &["# Rome", "", "Rome is in Italy."]).await;
+    // b.txt cannot be chunked
+    mock_upload(&mut server, "b.txt").await;
+    server
+        .mock("POST", "/v1/chunks")
+        .match_body(Matcher::PartialJson(json!({ "filename": "b.txt" })))
+        .with_status(400)
+        .with_body(r#"{"error":"unsupported file"}"#)
+        .create_async()
+        .await;
+    let embeddings_mock = server
+        .mock("POST", "/v1/embeddings")
+        .match_body(Matcher::PartialJson(json!({
+            "vdb_server_url": "http://localhost:6333",
+            "vdb_collection_name": "docs",
+        })))
+        .with_status(200)
+        .with_body(EMBEDDINGS_RESPONSE)
+        .expect(4)
+        .create_async()
+        .await;
+
+    let events = Arc::new(Mutex::new(Vec::new()));
+    let recorded = events.clone();
+    let options = IngestOptions::new()
+        .with_batch_size(1)
+        .with_concurrency(2)
+        .with_progress(move |event| recorded.lock().unwrap().push(event.clone()));
+
+    let client = Client::new(server.url()).unwrap();
+    let summary = client.ingest([&root], params(), options).await.unwrap();
+
+    assert_eq!(summary.collection, "docs");
+    assert_eq!(summary.chunks, 4);
+    assert!(!summary.is_complete());
+    let ingested = summary
+        .ingested
+        .iter()
+        .map(|file| (file.path.clone(), file.chunks))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        ingested,
+        vec![
+            (root.join("a.txt"), 2),
+            (root.join("guides").join("c.md"), 2)
+        ]
+    );
+    assert_eq!(summary.failed.len(), 1);
+    assert_eq!(summary.failed[0].path, root.join("b.txt"));
+    assert_eq!(summary.failed[0].indexed, 0);
+
+    let events = events.lock().unwrap().clone();
+    let events_of = |path: &Path| {
+        events
+            .iter()
+            .filter(|event| match event {
+                IngestEvent::Chunked { path: p, .. }
+                | IngestEvent::Embedded { path: p, .. }
+                | IngestEvent::Completed { path: p, .. }
+                | IngestEvent::Failed { path: p, .. } => p == path,
+            })
+            .cloned()
+            .collect::<Vec<_>>()
+    };
+    let a = root.join("a.txt");
+    assert_eq!(
+        events_of(&a),
+        vec![
+            IngestEvent::Chunked {
+                path: a.clone(),
+                chunks: 2
+            },
+            IngestEvent::Embedded {
+                path: a.clone(),
+                indexed: 1,
+                chunks: 2
+            },
+            IngestEvent::Embedded {
+                path: a.clone(),
+                indexed: 2,
+                chunks: 2
+            },
+            IngestEvent::Completed {
+                path: a.clone(),
+                chunks: 2
+            },
+        ]
+    );
+    let b = events_of(&root.join("b.txt"));
+    assert_eq!(b.len(), 1);
+    assert!(matches!(b[0], IngestEvent::Failed { .. }));
+
+    embeddings_mock.assert_async().await;
+    std::fs::remove_dir_all(&root).unwrap();
+}
+
+#[tokio::test]
+async fn test_ingest_batches_chunks() {
+    let root = documents("batches");
+    let mut server = mockito::Server::new_async().await;
+    mock_file(&mut server, "c.md", &["one", "two", "three"]).await;
+    let first_batch = server
+        .mock("POST", "/v1/embeddings")
+        .match_body(Matcher::PartialJson(json!({ "input": ["one", "two"] })))
+        .with_status(200)
+        .with_body(EMBEDDINGS_RESPONSE)
+        .create_async()
+        .await;
+    let second_batch = server
+        .mock("POST", "/v1/embeddings")
+        .match_body(Matcher::PartialJson(json!({ "input": ["three"] })))
+        .with_status(200)
+        .with_body(EMBEDDINGS_RESPONSE)
+        .create_async()
+        .await;
+
+    let client = Client::new(server.url()).unwrap();
+    let options = IngestOptions::new()
+        .with_batch_size(2)
+        .with_extensions(vec!["md".to_string()]);
+    let summary = client.ingest([&root], params(), options).await.unwrap();
+
+    assert!(summary.is_complete());
+    assert_eq!(summary.chunks, 3);
+    first_batch.assert_async().await;
+    second_batch.assert_async().await;
+    std::fs::remove_dir_all(&root).unwrap();
+}
+
+#[cfg(unix)]
+#[tokio::test]
+async fn test_ingest_skips_symlinked_directories() {
+    let root = documents("symlinks");
+    // a link to a parent directory would make a recursive search loop
+    std::os::unix::fs::symlink(&root, root.join("guides").join("loop")).unwrap();
+
+    let mut server = mockito::Server::new_async().await;
+    mock_file(&mut server, "c.md", &["# Rome", "Rome is in Italy."]).await;
+    let _embeddings_mock = server
+        .mock("POST", "/v1/embeddings")
+        .with_status(200)
+        .with_body(EMBEDDINGS_RESPONSE)
+        .create_async()
+        .await;
+
+    let client = Client::new(server.url()).unwrap();
+    let options = IngestOptions::new().with_extensions(vec!["md".to_string()]);
+    let summary = client.ingest([&root], params(), options).await.unwrap();
+
+    assert!(summary.is_complete());
+    assert_eq!(summary.ingested.len(), 1);
+    assert_eq!(summary.ingested[0].path, root.join("guides").join("c.md"));
+    std::fs::remove_dir_all(&root).unwrap();
+}
+
+#[tokio::test]
+async fn test_ingest_invalid_arguments() {
+    let client = Client::new("http://localhost:8080").unwrap();
+
+    let result = client
+        .ingest(
+            ["a.txt"],
+            params(),
+            IngestOptions::new().with_concurrency(0),
+        )
+        .await;
+    assert!(matches!(result, Err(LlamaEdgeError::InvalidArgument(_))));
+
+    let params = EmbeddingsParams {
+        vdb_collection_name: None,
+        ..params()
+    };
+    let result = client
+        .ingest(["a.txt"], params, IngestOptions::default())
+        .await;
+    match result {
+        Err(LlamaEdgeError::InvalidArgument(message)) => {
+            assert!(message.contains("vdb_collection_name"), "{}", message)
+        }
+        other => panic!("unexpected result: {:?}", other),
+    }
+}
@@ -0,0 +1,273 @@
+#![cfg(feature = "rag")]
+
+use endpoints::chat::{
+    ChatCompletionRequestMessage, ChatCompletionUserMessage, ChatCompletionUserMessageContent,
+};
+use futures::StreamExt;
+use llamaedge::{
+    error::LlamaEdgeError,
+    params::{RagChatParams, RagVdbConfig},
+    Client,
+};
+use mockito::Matcher;
+use serde_json::json;
+
+const ANSWER_RESPONSE: &str = r#"{"id":"chatcmpl-1","object":"chat.completion","created":1,"model":"llama","choices":[{"index":0,"message":{"content":"Paris is the capital of France.","role":"assistant"},"finish_reason":"stop","logprobs":null}],"usage":{"prompt_tokens":30,"completion_tokens":7,"total_tokens":37}}"#;
+
+const RETRIEVE_RESPONSE: &str = r#"[{"points":[{"source":"Paris is the capital and largest city of France.","score":0.82}],"limit":5,"score_threshold":0.5}]"#;
+
+fn user_messages() -> Vec<ChatCompletionRequestMessage> {
+    vec![ChatCompletionRequestMessage::User(
+        ChatCompletionUserMessage::new(
+            ChatCompletionUserMessageContent::Text("What is the capital of France?".to_string()),
+            None,
+        ),
+    )]
+}
+
+fn params() -> RagChatParams {
+    RagChatParams {
+        model: Some("llama".to_string()),
+        stop: Some(vec!["<|end|>".to_string()]),
+        max_tokens: 256,
+        ..Default::default()
+    }
+}
+
+/// The generation parameters that must be sent with both the retrieval and the chat completion requests.
+fn generation_params() -> Matcher {
+    Matcher::PartialJson(json!({
+        "model": "llama",
+        "stop": ["<|end|>"],
+        "max_tokens": 256,
+    }))
+}
+
+#[tokio::test]
+async fn test_rag_chat() {
+    let mut server = mockito::Server::new_async().await;
+    let retrieve_mock = server
+        .mock("POST", "/v1/retrieve")
+        .match_body(generation_params())
+        .with_status(200)
+        .with_body(RETRIEVE_RESPONSE)
+        .create_async()
+        .await;
+    let chat_mock = server
+        .mock("POST", "/v1/chat/completions")
+        .match_body(generation_params())
+        .with_status(200)
+        .with_body(ANSWER_RESPONSE)
+        .create_async()
+        .await;
+
+    let client = Client::new(server.url()).unwrap();
+    let params = RagChatParams {
+        retrieve_sources: true,
+        ..params()
+    };
+    let rag_chat_completion = client.rag_chat(&user_messages(), params).await.unwrap();
+
+    assert_eq!(
+        rag_chat_completion.answer(),
+        "Paris is the capital of France."
+    );
+    assert_eq!(rag_chat_completion.sources.len(), 1);
+    let points = rag_chat_completion.sources[0].points.as_ref().unwrap();
+    assert_eq!(
+        points[0].source,
+        "Paris is the capital and largest city of France."
+    );
+    retrieve_mock.assert_async().await;
+    chat_mock.assert_async().await;
+}
+
+#[tokio::test]
+async fn test_rag_chat_without_sources() {
+    let mut server = mockito::Server::new_async().await;
+    // the sources are only retrieved on request
+    let retrieve_mock = server
+        .mock("POST", "/v1/retrieve")
+        .expect(0)
+        .create_async()
+        .await;
+    let chat_mock = server
+        .mock("POST", "/v1/chat/completions")
+        .with_status(200)
+        .with_body(ANSWER_RESPONSE)
+        .create_async()
+        .await;
+
+    let client = Client::new(server.url()).unwrap();
+    let rag_chat_completion = client.rag_chat(&user_messages(), params()).await.unwrap();
+
+    assert_eq!(
+        rag_chat_completion.answer(),
+        "Paris is the capital of France."
+    );
+    assert!(rag_chat_completion.sources.is_empty());
+    retrieve_mock.assert_async().await;
+    chat_mock.assert_async().await;
+}
+
+#[tokio::test]
+async fn test_rag_chat_stream() {
+    let mut server = mockito::Server::new_async().await;
+    // the retrieval request does not carry the stream fields of the chat completion
+    let retrieve_mock = server
+        .mock("POST", "/v1/retrieve")
+        .match_request(|request| {
+            let body: serde_json::Value = serde_json::from_slice(request.body().unwrap()).unwrap();
+            body["stream"].is_null() && body["stream_options"].is_null()
+        })
+        .with_status(200)
+        .with_body(RETRIEVE_RESPONSE)
+        .create_async()
+        .await;
+    let chunk = r#"{"id":"chatcmpl-1","choices":[{"index":0,"delta":{"content":"Paris","role":"assistant"},"logprobs":null,"finish_reason":null}],"created":1,"model":"llama","system_fingerprint":"fp","object":"chat.completion.chunk"}"#;
+    let chat_mock = server
+        .mock("POST", "/v1/chat/completions")
+        .match_body(Matcher::PartialJson(json!({ "stream": true })))
+        .with_status(200)
+        .with_header("content-type", "text/event-stream")
+        .with_body(format!("data: {}\n\ndata: [DONE]\n\n", chunk))
+        .create_async()
+        .await;
+
+    let client = Client::new(server.url()).unwrap();
+    let rag_chat_stream = client
+        .rag_chat_stream(
+            &user_messages(),
+            RagChatParams {
+                retrieve_sources: true,
+                ..params()
+            },
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(rag_chat_stream.sources.len(), 1);
+    let chunks = rag_chat_stream.stream.collect::<Vec<_>>().await;
+    assert_eq!(chunks.len(), 1);
+    assert_eq!(
+        chunks[0].as_ref().unwrap().choices[0]
+            .delta
+            .content
+            .as_deref(),
+        Some("Paris")
+    );
+    retrieve_mock.assert_async().await;
+    chat_mock.assert_async().await;
+}
+
+#[tokio::test]
+async fn test_rag_retrieve_context_sends_stop_and_max_tokens() {
+    let mut server = mockito::Server::new_async().await;
+    let mock = server
+        .mock("POST", "/v1/retrieve")
+        .match_body(generation_params())
+        .with_status(200)
+        .with_body(RETRIEVE_RESPONSE)
+        .create_async()
+        .await;
+
+    let client = Client::new(server.url()).unwrap();
+    let sources = client
+        .rag_retrieve_context(&user_messages(), params())
+        .await
+        .unwrap();
+
+    assert_eq!(sources.len(), 1);
+    mock.assert_async().await;
+}
+
+#[tokio::test]
+async fn test_rag_chat_empty_history() {
+    let client = Client::new("http://localhost:8080").unwrap();
+    let result = client.rag_chat(&[], RagChatParams::default()).await;
+    assert!(matches!(result, Err(LlamaEdgeError::InvalidArgument(_))));
+}
+
+#[test]
+fn test_rag_vdb_config_builder() {
+    let vdb_config = RagVdbConfig::builder("http://localhost:6333")
+        .with_collection("paris", 5, 0.5)
+        .with_collection("london", 3, 0.7)
+        .with_api_key("secret")
+        .build()
+        .unwrap();
+    assert_eq!(vdb_config.collection_name, vec!["paris", "london"]);
+    assert_eq!(vdb_config.limit, vec![5, 3]);
+    assert_eq!(vdb_config.score_threshold, vec![0.5, 0.7]);
+    assert_eq!(vdb_config.api_key.as_deref(), Some("secret"));
+
+    let invalid = [
+        (
+            RagVdbConfig::builder("localhost:6333").with_collection("paris", 5, 0.5),
+            "http or https",
+        ),
+        (
+            RagVdbConfig::builder("not a url").with_collection("paris", 5, 0.5),
+            "Invalid VectorDB server URL",
+        ),
+        (
+            RagVdbConfig::builder("http://localhost:6333"),
+            "At least one VectorDB collection",
+        ),
+        (
+            RagVdbConfig::builder("http://localhost:6333").with_collection(" ", 5, 0.5),
+            "cannot be empty",
+        ),
+        (
+            RagVdbConfig::builder("http://localhost:6333").with_collection("paris", 0, 0.5),
+            "The limit of the collection `paris` cannot be zero",
+        ),
+        (
+            RagVdbConfig::builder("http://localhost:6333").with_collection("paris", 5, 1.5),
+            "The score threshold of the collection `paris` must be between 0 and 1, but got 1.5",
+        ),
+        (
+            RagVdbConfig::builder("http://localhost:6333").with_collection("paris", 5, f32::NAN),
+            "must be between 0 and 1",
+        ),
+    ];
+    for (builder, expected) in invalid {
+        match builder.build() {
+            Err(LlamaEdgeError::InvalidArgument(message)) => {
+                assert!(message.contains(expected), "{}", message)
+            }
+            other => panic!("unexpected result: {:?}", other),
+        }
+    }
+}
+
+#[tokio::test]
+async fn test_rag_retrieve_context_rejects_mismatched_vdb_config() {
+    let mut server = mockito::Server::new_async().await;
+    let mock = server
+        .mock("POST", "/v1/retrieve")
+        .expect(0)
+        .create_async()
+        .await;
+
+    let client = Client::new(server.url()).unwrap();
+    let params = RagChatParams {
+        vdb_config: Some(RagVdbConfig {
+            server_url: "http://localhost:6333".to_string(),
+            collection_name: vec!["paris".to_string(), "london".to_string()],
+            limit: vec![5],
+            score_threshold: vec![0.5, 0.7],
+            api_key: None,
+        }),
+        ..params()
+    };
+    let result = client.rag_retrieve_context(&user_messages(), params).await;
+
+    match result {
+        Err(LlamaEdgeError::InvalidArgument(message)) => {
+            assert_eq!(message, "Expected 2 limits, one per collection, but got 1")
+        }
+        other => panic!("unexpected result: {:?}", other),
+    }
+    mock.assert_async().await;
+}
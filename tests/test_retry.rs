@@ -0,0 +1,215 @@
+use llamaedge::{endpoint::Endpoint, error::LlamaEdgeError, retry::RetryPolicy, Client};
+use reqwest::StatusCode;
+use std::time::Duration;
+use tokio::{io::AsyncWriteExt, net::TcpListener};
+
+const MODELS_RESPONSE: &str = r#"{"object":"list","data":[{"id":"llama","created":1,"object":"model","owned_by":"Not specified"}]}"#;
+
+fn fast_policy() -> RetryPolicy {
+    RetryPolicy::new()
+        .with_max_attempts(3)
+        .with_base_delay(Duration::from_millis(1))
+        .with_jitter(false)
+}
+
+#[tokio::test]
+async fn test_retry_until_success() {
+    let mut server = mockito::Server::new_async().await;
+    let unavailable = server
+        .mock("GET", "/v1/models")
+        .with_status(503)
+        .with_body("Loading model")
+        .expect(2)
+        .create_async()
+        .await;
+    let ok = server
+        .mock("GET", "/v1/models")
+        .with_status(200)
+        .with_body(MODELS_RESPONSE)
+        .expect(1)
+        .create_async()
+        .await;
+
+    let client = Client::builder(server.url())
+        .with_retry_policy(fast_policy())
+        .build()
+        .unwrap();
+    let result = client.models().await;
+    assert!(result.is_ok());
+
+    unavailable.assert_async().await;
+    ok.assert_async().await;
+}
+
+#[tokio::test]
+async fn test_retry_gives_up_after_max_attempts() {
+    let mut server = mockito::Server::new_async().await;
+    let unavailable = server
+        .mock("GET", "/v1/models")
+        .with_status(503)
+        .expect(3)
+        .create_async()
+        .await;
+
+    let client = Client::builder(server.url())
+        .with_retry_policy(fast_policy())
+        .build()
+        .unwrap();
+    let err = client.models().await.unwrap_err();
+    assert_eq!(err.status(), Some(StatusCode::SERVICE_UNAVAILABLE));
+
+    unavailable.assert_async().await;
+}
+
+#[tokio::test]
+async fn test_no_retry_by_default() {
+    let mut server = mockito::Server::new_async().await;
+    let unavailable = server
+        .mock("GET", "/v1/models")
+        .with_status(503)
+        .expect(1)
+        .create_async()
+        .await;
+
+    let client = Client::new(server.url()).unwrap();
+    assert!(client.models().await.is_err());
+
+    unavailable.assert_async().await;
+}
+
+#[tokio::test]
+async fn test_no_retry_on_client_error() {
+    let mut server = mockito::Server::new_async().await;
+    let bad_request = server
+        .mock("GET", "/v1/models")
+        .with_status(400)
+        .expect(1)
+        .create_async()
+        .await;
+
+    let client = Client::builder(server.url())
+        .with_retry_policy(fast_policy())
+        .build()
+        .unwrap();
+    assert!(client.models().await.is_err());
+
+    bad_request.assert_async().await;
+}
+
+#[tokio::test]
+async fn test_endpoint_retry_policy() {
+    let mut server = mockito::Server::new_async().await;
+    let unavailable = server
+        .mock("GET", "/v1/models")
+        .with_status(503)
+        .expect(1)
+        .create_async()
+        .await;
+
+    let client = Client::builder(server.url())
+        .with_retry_policy(fast_policy())
+        .with_endpoint_retry_policy(Endpoint::Models, RetryPolicy::none())
+        .build()
+        .unwrap();
+    assert!(client.models().await.is_err());
+
+    unavailable.assert_async().await;
+}
+
+#[tokio::test]
+async fn test_retry_file_reads() {
+    let mut server = mockito::Server::new_async().await;
+    let unavailable = server
+        .mock("GET", "/v1/files/file_1")
+        .with_status(503)
+        .expect(1)
+        .create_async()
+        .await;
+    let ok = server
+        .mock("GET", "/v1/files/file_1")
+        .with_status(200)
+        .with_body(r#"{"id":"file_1","bytes":13,"created_at":1,"filename":"notes.txt","object":"file","purpose":"assistants"}"#)
+        .expect(1)
+        .create_async()
+        .await;
+
+    // the files endpoint is not idempotent because of uploads, but reading a file is
+    let client = Client::builder(server.url())
+        .with_retry_policy(fast_policy())
+        .build()
+        .unwrap();
+    let file = client.retrieve_file("file_1").await.unwrap();
+    assert_eq!(file.filename, "notes.txt");
+
+    unavailable.assert_async().await;
+    ok.assert_async().await;
+}
+
+#[tokio::test]
+async fn test_retry_on_closed_connection() {
+    // the first connection is closed without a response, the second one gets the models
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move {
+        let (closed, _) = listener.accept().await.unwrap();
+        drop(closed);
+        let (mut stream, _) = listener.accept().await.unwrap();
+        let response = format!(
+            "HTTP/1.1 200 OK\r\ncontent-type: application/json\r\ncontent-length: {}\r\n\r\n{}",
+            MODELS_RESPONSE.len(),
+            MODELS_RESPONSE
+        );
+        stream.write_all(response.as_bytes()).await.unwrap();
+    });
+
+    let client = Client::builder(format!("http://{}", addr))
+        .with_retry_policy(fast_policy())
+        .build()
+        .unwrap();
+    let models = client.models().await.unwrap();
+    assert_eq!(models.len(), 1);
+}
+
+#[tokio::test]
+async fn test_no_retry_on_request_body_error() {
+    let mut server = mockito::Server::new_async().await;
+    let _mock = server
+        .mock("POST", "/upload")
+        .with_status(200)
+        .create_async()
+        .await;
+
+    // a body failing while it is sent is a request error, but not a connection error
+    let body = reqwest::Body::wrap_stream(futures::stream::once(async {
+        Err::<bytes::Bytes, _>(std::io::Error::other("broken body"))
+    }));
+    let err = reqwest::Client::new()
+        .post(format!("{}/upload", server.url()))
+        .body(body)
+        .send()
+        .await
+        .unwrap_err();
+    assert!(err.is_request() && !err.is_connect());
+
+    let err = LlamaEdgeError::from(err);
+    assert!(matches!(err, LlamaEdgeError::Transport(_)));
+    assert!(!err.is_retryable());
+    assert!(!fast_policy().should_retry(&err));
+}
+
+#[test]
+fn test_retry_delay() {
+    let policy = RetryPolicy::new()
+        .with_base_delay(Duration::from_millis(100))
+        .with_max_delay(Duration::from_millis(300))
+        .with_jitter(false);
+    assert_eq!(policy.delay(1), Duration::from_millis(100));
+    assert_eq!(policy.delay(2), Duration::from_millis(200));
+    assert_eq!(policy.delay(3), Duration::from_millis(300));
+    assert_eq!(policy.delay(40), Duration::from_millis(300));
+
+    let policy = policy.with_jitter(true);
+    for attempt in 1..10 {
+        assert!(policy.delay(attempt) <= Duration::from_millis(300));
+    }
+}
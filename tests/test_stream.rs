@@ -0,0 +1,94 @@
+use bytes::Bytes;
+use futures::{stream, StreamExt};
+use llamaedge::{error::LlamaEdgeError, stream::ChatCompletionStream};
+
+fn chunk_json(content: &str) -> String {
+    format!(
+        r#"{{"id":"chatcmpl-1","choices":[{{"index":0,"delta":{{"content":"{}","role":"assistant"}},"logprobs":null,"finish_reason":null}}],"created":1,"model":"test","system_fingerprint":"fp","object":"chat.completion.chunk"}}"#,
+        content
+    )
+}
+
+fn usage_json() -> String {
+    r#"{"id":"chatcmpl-1","choices":[],"created":1,"model":"test","system_fingerprint":"fp","object":"chat.completion.chunk","usage":{"prompt_tokens":3,"completion_tokens":2,"total_tokens":5}}"#.to_string()
+}
+
+fn byte_stream(frames: Vec<Vec<u8>>) -> ChatCompletionStream {
+    ChatCompletionStream::new(stream::iter(
+        frames
+            .into_iter()
+            .map(|frame| Ok::<_, LlamaEdgeError>(Bytes::from(frame))),
+    ))
+}
+
+#[tokio::test]
+async fn test_stream_decodes_events_split_across_frames() {
+    let body = format!(
+        "data: {}\n\ndata: {}\n\ndata: {}\n\ndata: [DONE]\n\n",
+        chunk_json("Par"),
+        chunk_json("is"),
+        usage_json()
+    );
+
+    // split the body into tiny frames so that every event spans several frames
+    let frames = body
+        .as_bytes()
+        .chunks(7)
+        .map(|frame| frame.to_vec())
+        .collect();
+    let chunks = byte_stream(frames)
+        .collect::<Vec<_>>()
+        .await
+        .into_iter()
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+
+    assert_eq!(chunks.len(), 3);
+    let output = chunks
+        .iter()
+        .filter_map(|chunk| chunk.choices.first())
+        .filter_map(|choice| choice.delta.content.clone())
+        .collect::<String>();
+    assert_eq!(output, "Paris");
+    let usage = chunks[2].usage.unwrap();
+    assert_eq!(usage.total_tokens, 5);
+}
+
+#[tokio::test]
+async fn test_stream_handles_multibyte_character_on_frame_boundary() {
+    let body = format!("data: {}\r\n\r\ndata: [DONE]\r\n\r\n", chunk_json("巴黎"));
+    let bytes = body.as_bytes();
+    let split = body.find('巴').unwrap() + 1;
+    let frames = vec![bytes[..split].to_vec(), bytes[split..].to_vec()];
+
+    let chunks = byte_stream(frames).collect::<Vec<_>>().await;
+    assert_eq!(chunks.len(), 1);
+    let chunk = chunks.into_iter().next().unwrap().unwrap();
+    assert_eq!(chunk.choices[0].delta.content.as_deref(), Some("巴黎"));
+}
+
+#[tokio::test]
+async fn test_stream_ends_on_done() {
+    let body = format!(
+        ": keep-alive\n\ndata: {}\n\ndata: [DONE]\n\ndata: {}\n\n",
+        chunk_json("a"),
+        chunk_json("b")
+    );
+
+    let chunks = byte_stream(vec![body.into_bytes()])
+        .collect::<Vec<_>>()
+        .await;
+    assert_eq!(chunks.len(), 1);
+    assert!(chunks[0].is_ok());
+}
+
+#[tokio::test]
+async fn test_stream_reports_invalid_chunk() {
+    let body = "data: {\"not\": \"a chunk\"}\n\ndata: [DONE]\n\n".to_string();
+
+    let chunks = byte_stream(vec![body.into_bytes()])
+        .collect::<Vec<_>>()
+        .await;
+    assert_eq!(chunks.len(), 1);
+    assert!(chunks[0].is_err());
+}
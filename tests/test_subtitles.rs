@@ -0,0 +1,140 @@
+#![cfg(feature = "audio")]
+
+use llamaedge::{
+    audio::{
+        subtitles::{Cue, Subtitles},
+        Transcript, TranscriptSegment, VerboseTranscript,
+    },
+    error::LlamaEdgeError,
+};
+use std::time::Duration;
+
+const SRT: &str = "1\r\n00:00:00,000 --> 00:00:02,500\r\nThis is a test record\r\nfor whisper.cpp\r\n\r\n2\r\n00:00:02,500 --> 00:01:04,020\r\nSecond cue\r\n";
+
+#[test]
+fn test_parse_and_render_srt() {
+    let subtitles = Subtitles::parse_srt(SRT).unwrap();
+    assert_eq!(
+        subtitles.cues,
+        vec![
+            Cue::new(
+                Duration::ZERO,
+                Duration::from_millis(2500),
+                "This is a test record\nfor whisper.cpp"
+            ),
+            Cue::new(
+                Duration::from_millis(2500),
+                Duration::from_millis(64_020),
+                "Second cue"
+            ),
+        ]
+    );
+
+    assert_eq!(subtitles.to_srt(), SRT.replace("\r\n", "\n"));
+    assert_eq!(
+        Subtitles::parse_srt(&subtitles.to_srt()).unwrap(),
+        subtitles
+    );
+}
+
+#[test]
+fn test_parse_and_render_vtt() {
+    let vtt = "WEBVTT - transcript\n\nNOTE generated by whisper\n\nintro\n00:01.000 --> 00:02.5 align:start position:10%\nHello\n\n01:00:00.000 --> 01:00:01.000\nworld\n";
+    let subtitles = Subtitles::parse_vtt(vtt).unwrap();
+    assert_eq!(
+        subtitles.cues,
+        vec![
+            Cue::new(Duration::from_secs(1), Duration::from_millis(2500), "Hello"),
+            Cue::new(
+                Duration::from_secs(3600),
+                Duration::from_secs(3601),
+                "world"
+            ),
+        ]
+    );
+    assert_eq!(subtitles.text(), "Hello world");
+
+    assert_eq!(
+        subtitles.to_vtt(),
+        "WEBVTT\n\n00:00:01.000 --> 00:00:02.500\nHello\n\n01:00:00.000 --> 01:00:01.000\nworld\n"
+    );
+}
+
+#[test]
+fn test_parse_invalid_subtitles() {
+    let result = Subtitles::parse_vtt("1\n00:00:00.000 --> 00:00:01.000\nHello\n");
+    assert!(matches!(result, Err(LlamaEdgeError::Decode { .. })));
+
+    let result = Subtitles::parse_srt("1\n00:00:00,000 --> 00:00:61,000\nHello\n");
+    assert!(matches!(result, Err(LlamaEdgeError::Decode { .. })));
+
+    let result = Subtitles::parse_srt("1\nHello\n");
+    assert!(matches!(result, Err(LlamaEdgeError::Decode { .. })));
+
+    // the hour field would overflow the number of milliseconds
+    let result = Subtitles::parse_srt(
+        "1\n18446744073709551615:00:00,000 --> 18446744073709551615:00:01,000\nHello\n",
+    );
+    assert!(matches!(result, Err(LlamaEdgeError::Decode { .. })));
+}
+
+#[test]
+fn test_shift_and_merge_subtitles() {
+    let mut first = Subtitles::new(vec![Cue::new(
+        Duration::ZERO,
+        Duration::from_secs(2),
+        "first",
+    )]);
+    let second = Subtitles::new(vec![Cue::new(
+        Duration::ZERO,
+        Duration::from_secs(3),
+        "second",
+    )]);
+
+    first.append(second.clone(), Duration::from_secs(30));
+    assert_eq!(first.cues[1].start, Duration::from_secs(30));
+    assert_eq!(first.cues[1].end, Duration::from_secs(33));
+
+    let mut shifted = second.clone();
+    shifted.shift(Duration::from_secs(10));
+    let merged = Subtitles::merge(vec![shifted, second]);
+    assert_eq!(merged.text(), "second second");
+    assert_eq!(merged.cues[0].start, Duration::ZERO);
+    assert_eq!(merged.cues[1].start, Duration::from_secs(10));
+
+    let mut late = Subtitles::new(vec![Cue::new(Duration::MAX, Duration::MAX, "late")]);
+    late.shift(Duration::from_secs(1));
+    assert_eq!(late.cues[0].end, Duration::MAX);
+}
+
+#[test]
+fn test_subtitles_from_transcript() {
+    let transcript = Transcript::Verbose(VerboseTranscript {
+        text: "Hello world".to_string(),
+        segments: vec![
+            TranscriptSegment {
+                start: 0.0,
+                end: 1.25,
+                text: " Hello".to_string(),
+                ..Default::default()
+            },
+            TranscriptSegment {
+                id: 1,
+                start: 1.25,
+                end: 2.0,
+                text: " world".to_string(),
+                ..Default::default()
+            },
+        ],
+        ..Default::default()
+    });
+
+    let subtitles = Subtitles::try_from(&transcript).unwrap();
+    assert_eq!(
+        subtitles.to_srt(),
+        "1\n00:00:00,000 --> 00:00:01,250\nHello\n\n2\n00:00:01,250 --> 00:00:02,000\nworld\n"
+    );
+
+    let result = Subtitles::try_from(&Transcript::Text("Hello world".to_string()));
+    assert!(matches!(result, Err(LlamaEdgeError::InvalidArgument(_))));
+}
@@ -0,0 +1,116 @@
+use endpoints::chat::{
+    ChatCompletionRequestMessage, ChatCompletionUserMessage, ChatCompletionUserMessageContent,
+};
+use llamaedge::{params::ChatParams, tools::ToolRegistry, Client};
+use mockito::Matcher;
+use serde_json::json;
+
+const TOOL_CALL_RESPONSE: &str = r#"{"id":"chatcmpl-1","object":"chat.completion","created":1,"model":"llama","choices":[{"index":0,"message":{"content":null,"tool_calls":[{"id":"call-1","type":"function","function":{"name":"get_current_weather","arguments":"{\"location\":\"Paris\"}"}}],"role":"assistant"},"finish_reason":"tool_calls","logprobs":null}],"usage":{"prompt_tokens":10,"completion_tokens":5,"total_tokens":15}}"#;
+
+const ANSWER_RESPONSE: &str = r#"{"id":"chatcmpl-2","object":"chat.completion","created":2,"model":"llama","choices":[{"index":0,"message":{"content":"It is sunny in Paris.","tool_calls":[],"role":"assistant"},"finish_reason":"stop","logprobs":null}],"usage":{"prompt_tokens":20,"completion_tokens":6,"total_tokens":26}}"#;
+
+fn weather_registry() -> ToolRegistry {
+    let mut registry = ToolRegistry::new();
+    registry
+        .register(
+            "get_current_weather",
+            "Get the current weather in a given location",
+            json!({
+                "type": "object",
+                "properties": {
+                    "location": {
+                        "type": "string",
+                        "description": "The city, e.g. Paris"
+                    }
+                },
+                "required": ["location"]
+            }),
+            |args| async move {
+                let location = args["location"].as_str().unwrap_or_default().to_string();
+                Ok(format!("sunny in {}", location))
+            },
+        )
+        .unwrap();
+    registry
+}
+
+fn user_message(text: &str) -> Vec<ChatCompletionRequestMessage> {
+    vec![ChatCompletionRequestMessage::User(
+        ChatCompletionUserMessage::new(
+            ChatCompletionUserMessageContent::Text(text.to_string()),
+            None,
+        ),
+    )]
+}
+
+#[tokio::test]
+async fn test_run_with_tools() {
+    let mut server = mockito::Server::new_async().await;
+    let first = server
+        .mock("POST", "/v1/chat/completions")
+        .match_body(Matcher::Regex("get_current_weather".to_string()))
+        .with_status(200)
+        .with_body(TOOL_CALL_RESPONSE)
+        .expect(1)
+        .create_async()
+        .await;
+    let second = server
+        .mock("POST", "/v1/chat/completions")
+        .match_body(Matcher::Regex("sunny in Paris".to_string()))
+        .with_status(200)
+        .with_body(ANSWER_RESPONSE)
+        .expect(1)
+        .create_async()
+        .await;
+
+    let client = Client::new(server.url()).unwrap();
+    let messages = user_message("What is the weather like in Paris?");
+    let run = client
+        .run_with_tools(&messages, &ChatParams::default(), &weather_registry(), 5)
+        .await
+        .unwrap();
+
+    assert_eq!(run.iterations, 2);
+    // user, assistant tool call, tool result, final answer
+    assert_eq!(run.messages.len(), 4);
+    assert!(matches!(
+        run.messages[2],
+        ChatCompletionRequestMessage::Tool(_)
+    ));
+    assert_eq!(
+        run.chat_completion.choices[0].message.content.as_deref(),
+        Some("It is sunny in Paris.")
+    );
+
+    first.assert_async().await;
+    second.assert_async().await;
+}
+
+#[tokio::test]
+async fn test_run_with_tools_max_iterations() {
+    let mut server = mockito::Server::new_async().await;
+    server
+        .mock("POST", "/v1/chat/completions")
+        .with_status(200)
+        .with_body(TOOL_CALL_RESPONSE)
+        .expect(2)
+        .create_async()
+        .await;
+
+    let client = Client::new(server.url()).unwrap();
+    let messages = user_message("What is the weather like in Paris?");
+    let result = client
+        .run_with_tools(&messages, &ChatParams::default(), &weather_registry(), 2)
+        .await;
+    assert!(result.is_err());
+}
+
+#[test]
+fn test_register_invalid_schema() {
+    let mut registry = ToolRegistry::new();
+    let result = registry.register("noop", "Do nothing", json!("not a schema"), |_| async {
+        Ok(String::new())
+    });
+    assert!(result.is_err());
+    assert!(registry.is_empty());
+}
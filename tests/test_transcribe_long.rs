@@ -0,0 +1,159 @@
+#![cfg(feature = "audio")]
+
+use endpoints::audio::transcription::TranscriptionObject;
+use llamaedge::{
+    audio::long::LongTranscriptionOptions, error::LlamaEdgeError, params::TranscriptionParams,
+    Client,
+};
+use mockito::Matcher;
+use std::time::Duration;
+
+#[tokio::test]
+async fn test_transcribe_long_stitches_windows() {
+    let mut server = mockito::Server::new_async().await;
+
+    // `test.wav` lasts about 4.4 seconds, so it is split into the windows [0, 2], [1.5, 3.5] and [3, 4.4]
+    let chunk_mocks = [
+        (
+            Matcher::Regex(r#"filename="chunk_0.wav""#.to_string()),
+            r#"{"text":"Hello world","segments":[{"start":0.0,"end":1.0,"text":" Hello"},{"start":1.6,"end":2.0,"text":" world"}]}"#,
+        ),
+        (
+            Matcher::AllOf(vec![
+                Matcher::Regex(r#"filename="chunk_1.wav""#.to_string()),
+                Matcher::Regex("name=\"prompt\"\r\n(.*\r\n)*\r\nHello world\r\n".to_string()),
+            ]),
+            r#"{"text":"world this is","segments":[{"start":0.1,"end":0.5,"text":" world"},{"start":1.0,"end":1.5,"text":" this is"}]}"#,
+        ),
+        (
+            Matcher::AllOf(vec![
+                Matcher::Regex(r#"filename="chunk_2.wav""#.to_string()),
+                Matcher::Regex("name=\"prompt\"\r\n(.*\r\n)*\r\nworld this is\r\n".to_string()),
+            ]),
+            r#"{"text":"is a test","segments":[{"start":0.0,"end":0.4,"text":" is"},{"start":0.5,"end":1.2,"text":" a test"}]}"#,
+        ),
+    ];
+    let mut mocks = Vec::new();
+    for (matcher, body) in chunk_mocks {
+        mocks.push(
+            server
+                .mock("POST", "/v1/audio/transcriptions")
+                .match_body(matcher)
+                .with_status(200)
+                .with_body(body)
+                .create_async()
+                .await,
+        );
+    }
+
+    let client = Client::new(server.url()).unwrap();
+    let options = LongTranscriptionOptions::new()
+        .with_window(Duration::from_secs(2))
+        .with_overlap(Duration::from_millis(500))
+        // each chunk still waits for the text of the previous one
+        .with_concurrency(4);
+    let transcript = client
+        .transcribe_long(
+            "tests/assets/test.wav",
+            "en",
+            TranscriptionParams::default(),
+            options,
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(transcript.text, "Hello world this is a test");
+    let starts = transcript
+        .segments
+        .iter()
+        .map(|segment| (segment.start * 10.0).round() / 10.0)
+        .collect::<Vec<_>>();
+    assert_eq!(starts, vec![0.0, 1.6, 2.5, 3.5]);
+    assert!((transcript.duration.unwrap() - 4.4).abs() < 0.1);
+    assert_eq!(
+        TranscriptionObject::from(transcript).text,
+        "Hello world this is a test"
+    );
+
+    for mock in mocks {
+        mock.assert_async().await;
+    }
+}
+
+#[tokio::test]
+async fn test_transcribe_long_ignores_out_of_range_timestamps() {
+    let mut server = mockito::Server::new_async().await;
+    let mock = server
+        .mock("POST", "/v1/audio/transcriptions")
+        .with_status(200)
+        .with_body(r#"{"text":"Hello world","segments":[{"start":0.0,"end":1.0,"text":" Hello"},{"start":1e300,"end":1e300,"text":" world"}]}"#)
+        .create_async()
+        .await;
+
+    let client = Client::new(server.url()).unwrap();
+    let options = LongTranscriptionOptions::new().with_window(Duration::from_secs(10));
+    let transcript = client
+        .transcribe_long(
+            "tests/assets/test.wav",
+            "en",
+            TranscriptionParams::default(),
+            options,
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(transcript.text, "Hello");
+    assert_eq!(transcript.segments.len(), 1);
+    mock.assert_async().await;
+}
+
+#[tokio::test]
+async fn test_transcribe_long_invalid_input() {
+    let client = Client::new("http://localhost:8080").unwrap();
+
+    let options = LongTranscriptionOptions::new()
+        .with_window(Duration::from_secs(2))
+        .with_overlap(Duration::from_secs(2));
+    let result = client
+        .transcribe_long(
+            "tests/assets/test.wav",
+            "en",
+            TranscriptionParams::default(),
+            options,
+        )
+        .await;
+    assert!(matches!(result, Err(LlamaEdgeError::InvalidArgument(_))));
+
+    let result = client
+        .transcribe_long(
+            "tests/assets/paris.txt",
+            "en",
+            TranscriptionParams::default(),
+            LongTranscriptionOptions::default(),
+        )
+        .await;
+    assert!(matches!(result, Err(LlamaEdgeError::InvalidArgument(_))));
+
+    // a 16 kHz mono 16-bit PCM file without samples
+    let mut empty = b"RIFF\x24\0\0\0WAVEfmt \x10\0\0\0\x01\0\x01\0".to_vec();
+    empty.extend_from_slice(&16_000u32.to_le_bytes());
+    empty.extend_from_slice(&32_000u32.to_le_bytes());
+    empty.extend_from_slice(b"\x02\0\x10\0data\0\0\0\0");
+    let path = std::env::temp_dir().join(format!("llamaedge-empty-{}.wav", std::process::id()));
+    std::fs::write(&path, empty).unwrap();
+    let result = client
+        .transcribe_long(
+            &path,
+            "en",
+            TranscriptionParams::default(),
+            LongTranscriptionOptions::default(),
+        )
+        .await;
+    std::fs::remove_file(&path).unwrap();
+    match result {
+        Err(LlamaEdgeError::InvalidArgument(message)) => {
+            assert!(message.contains("no audio"), "{}", message)
+        }
+        other => panic!("unexpected result: {:?}", other),
+    }
+}
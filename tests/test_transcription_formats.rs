@@ -0,0 +1,140 @@
+#![cfg(feature = "audio")]
+
+use llamaedge::{
+    audio::{Transcript, TranscriptionFormat},
+    error::LlamaEdgeError,
+    params::{TranscriptionParams, TranslationParams},
+    Client,
+};
+use mockito::Matcher;
+
+#[test]
+fn test_transcription_format_from_str() {
+    assert_eq!(
+        "verbose_json".parse::<TranscriptionFormat>().unwrap(),
+        TranscriptionFormat::VerboseJson
+    );
+    assert_eq!(TranscriptionFormat::Vtt.to_string(), "vtt");
+    assert!(matches!(
+        "docx".parse::<TranscriptionFormat>(),
+        Err(LlamaEdgeError::InvalidArgument(_))
+    ));
+}
+
+#[tokio::test]
+async fn test_transcribe_json_and_text() {
+    let mut server = mockito::Server::new_async().await;
+    let json_mock = server
+        .mock("POST", "/v1/audio/transcriptions")
+        .match_body(Matcher::Regex(
+            "name=\"response_format\"\r\n(.*\r\n)*\r\njson\r\n".to_string(),
+        ))
+        .with_status(200)
+        .with_body(r#"{"text":"This is a test record for whisper.cpp"}"#)
+        .create_async()
+        .await;
+    let text_mock = server
+        .mock("POST", "/v1/audio/transcriptions")
+        .match_body(Matcher::Regex(
+            "name=\"response_format\"\r\n(.*\r\n)*\r\ntext\r\n".to_string(),
+        ))
+        .with_status(200)
+        .with_body("This is a test record for whisper.cpp\n")
+        .create_async()
+        .await;
+
+    let client = Client::new(server.url()).unwrap();
+
+    let transcript = client
+        .transcribe(
+            "tests/assets/test.wav",
+            "en",
+            TranscriptionParams::default(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(
+        transcript,
+        Transcript::Text("This is a test record for whisper.cpp".to_string())
+    );
+
+    let params = TranscriptionParams {
+        response_format: TranscriptionFormat::Text,
+        ..Default::default()
+    };
+    let transcript = client
+        .transcribe("tests/assets/test.wav", "en", params)
+        .await
+        .unwrap();
+    assert_eq!(
+        transcript.text().trim(),
+        "This is a test record for whisper.cpp"
+    );
+
+    json_mock.assert_async().await;
+    text_mock.assert_async().await;
+}
+
+#[tokio::test]
+async fn test_transcribe_verbose_json() {
+    let mut server = mockito::Server::new_async().await;
+    let mock = server
+        .mock("POST", "/v1/audio/transcriptions")
+        .match_body(Matcher::Regex(
+            "name=\"timestamp_granularities\\[\\]\"\r\n(.*\r\n)*\r\nword\r\n".to_string(),
+        ))
+        .with_status(200)
+        .with_body(
+            r#"{"task":"transcribe","language":"english","duration":2.5,"text":"Hello world","segments":[{"id":0,"seek":0,"start":0.0,"end":2.5,"text":"Hello world","tokens":[50364,2425,1002],"temperature":0.0,"avg_logprob":-0.2,"compression_ratio":0.8,"no_speech_prob":0.01}],"words":[{"word":"Hello","start":0.0,"end":1.0},{"word":"world","start":1.2,"end":2.5}]}"#,
+        )
+        .create_async()
+        .await;
+
+    let client = Client::new(server.url()).unwrap();
+    let params = TranscriptionParams {
+        response_format: TranscriptionFormat::VerboseJson,
+        timestamp_granularities: Some(vec![
+            endpoints::audio::transcription::TimestampGranularity::Segment,
+            endpoints::audio::transcription::TimestampGranularity::Word,
+        ]),
+        ..Default::default()
+    };
+    let transcript = client
+        .transcribe("tests/assets/test.wav", "en", params)
+        .await
+        .unwrap();
+
+    let verbose = transcript.verbose().unwrap();
+    assert_eq!(verbose.text, "Hello world");
+    assert_eq!(verbose.segments.len(), 1);
+    assert_eq!(verbose.segments[0].end, 2.5);
+    assert_eq!(verbose.words[1].word, "world");
+
+    mock.assert_async().await;
+}
+
+#[tokio::test]
+async fn test_translate_srt() {
+    let srt = "1\n00:00:00,000 --> 00:00:02,000\nThis is a Chinese broadcast.\n";
+
+    let mut server = mockito::Server::new_async().await;
+    let mock = server
+        .mock("POST", "/v1/audio/translations")
+        .with_status(200)
+        .with_body(srt)
+        .create_async()
+        .await;
+
+    let client = Client::new(server.url()).unwrap();
+    let params = TranslationParams {
+        response_format: TranscriptionFormat::Srt,
+        ..Default::default()
+    };
+    let translation = client
+        .translate("tests/assets/test_zh.wav", "zh", params)
+        .await
+        .unwrap();
+    assert_eq!(translation, Transcript::Srt(srt.to_string()));
+
+    mock.assert_async().await;
+}
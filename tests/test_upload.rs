@@ -0,0 +1,133 @@
+use llamaedge::{
+    error::LlamaEdgeError,
+    upload::{detect_mime_type, UploadFile},
+    Client,
+};
+use mockito::Matcher;
+
+const FILE_OBJECT: &str = r#"{"id":"file_1","bytes":13,"created_at":1733817460,"filename":"notes.md","object":"file","purpose":"assistants"}"#;
+
+#[test]
+fn test_detect_mime_type() {
+    // magic bytes
+    assert_eq!(
+        detect_mime_type("image", b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR"),
+        "image/png"
+    );
+    assert_eq!(
+        detect_mime_type("photo.png", b"\xff\xd8\xff\xe0\0\x10JFIF"),
+        "image/jpeg"
+    );
+    assert_eq!(
+        detect_mime_type("speech", b"RIFF\x24\x08\0\0WAVEfmt "),
+        "audio/wav"
+    );
+
+    // extension
+    assert_eq!(detect_mime_type("notes.md", b"# Title"), "text/markdown");
+    assert_eq!(detect_mime_type("NOTES.TXT", b"hello"), "text/plain");
+
+    // fallback
+    assert_eq!(detect_mime_type("README", b"hello"), "text/plain");
+    // the first bytes end in the middle of a multibyte character
+    assert_eq!(
+        detect_mime_type("README", &"déjà vu, encoé!".as_bytes()[..16]),
+        "text/plain"
+    );
+    assert_eq!(
+        detect_mime_type("data", &[0x00, 0x9f, 0x92, 0x96]),
+        "application/octet-stream"
+    );
+}
+
+#[tokio::test]
+async fn test_upload_file_content_type() {
+    let path = std::env::temp_dir().join("llamaedge_test_upload.md");
+    std::fs::write(&path, "# Hello, world").unwrap();
+
+    let mut server = mockito::Server::new_async().await;
+    let detected_mock = server
+        .mock("POST", "/v1/files")
+        .match_body(Matcher::Regex(
+            r#"filename="llamaedge_test_upload.md"\r\nContent-Type: text/markdown"#.to_string(),
+        ))
+        .with_status(200)
+        .with_body(FILE_OBJECT)
+        .create_async()
+        .await;
+    let override_mock = server
+        .mock("POST", "/v1/files")
+        .match_body(Matcher::Regex("Content-Type: text/plain".to_string()))
+        .with_status(200)
+        .with_body(FILE_OBJECT)
+        .create_async()
+        .await;
+
+    let client = Client::new(server.url()).unwrap();
+    client.upload_file(&path).await.unwrap();
+    client
+        .upload_file(UploadFile::from_path(&path).with_mime_type("text/plain"))
+        .await
+        .unwrap();
+
+    detected_mock.assert_async().await;
+    override_mock.assert_async().await;
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[tokio::test]
+async fn test_upload_file_invalid_arguments() {
+    let client = Client::new("http://localhost:8080").unwrap();
+
+    let result = client.upload_file("tests/assets/missing.txt").await;
+    assert!(matches!(result, Err(LlamaEdgeError::InvalidArgument(_))));
+
+    let result = client
+        .upload_file(UploadFile::from_path("tests/assets/test.wav").with_mime_type("not a mime"))
+        .await;
+    assert!(matches!(result, Err(LlamaEdgeError::InvalidArgument(_))));
+}
+
+#[tokio::test]
+async fn test_upload_file_from_bytes_and_reader() {
+    let mut server = mockito::Server::new_async().await;
+    let bytes_mock = server
+        .mock("POST", "/v1/files")
+        .match_body(Matcher::AllOf(vec![
+            Matcher::Regex(r#"filename="notes.md"\r\nContent-Type: text/markdown"#.to_string()),
+            Matcher::Regex("# Hello from memory".to_string()),
+        ]))
+        .with_status(200)
+        .with_body(FILE_OBJECT)
+        .create_async()
+        .await;
+    let reader_mock = server
+        .mock("POST", "/v1/files")
+        .match_body(Matcher::AllOf(vec![
+            Matcher::Regex(r#"filename="speech"\r\nContent-Type: audio/wav"#.to_string()),
+            Matcher::Regex("WAVEfmt ".to_string()),
+        ]))
+        .with_status(200)
+        .with_body(FILE_OBJECT)
+        .create_async()
+        .await;
+
+    let client = Client::new(server.url()).unwrap();
+    client
+        .upload_file(UploadFile::from_bytes(
+            bytes::Bytes::from_static(b"# Hello from memory"),
+            "notes.md",
+        ))
+        .await
+        .unwrap();
+
+    // the content type is sniffed from the first bytes of the reader
+    let reader = std::io::Cursor::new(b"RIFF\x24\x08\0\0WAVEfmt \x10\0\0\0".to_vec());
+    client
+        .upload_file(UploadFile::from_reader(reader, "speech"))
+        .await
+        .unwrap();
+
+    bytes_mock.assert_async().await;
+    reader_mock.assert_async().await;
+}
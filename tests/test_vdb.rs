@@ -0,0 +1,162 @@
+#![cfg(feature = "rag")]
+
+use llamaedge::{
+    error::LlamaEdgeError,
+    params::RagVdbConfig,
+    rag::vdb::{Condition, Distance, Filter, VectorDbClient, VectorParams},
+};
+use mockito::Matcher;
+use serde_json::json;
+
+#[tokio::test]
+async fn test_manage_collections() {
+    let mut server = mockito::Server::new_async().await;
+    let list_mock = server
+        .mock("GET", "/collections")
+        .match_header("api-key", "secret")
+        .with_status(200)
+        .with_body(r#"{"result":{"collections":[{"name":"default"},{"name":"paris"}]},"status":"ok","time":0.001}"#)
+        .create_async()
+        .await;
+    let create_mock = server
+        .mock("PUT", "/collections/paris")
+        .match_header("api-key", "secret")
+        .match_body(Matcher::Json(json!({
+            "vectors": { "size": 768, "distance": "Cosine" }
+        })))
+        .with_status(200)
+        .with_body(r#"{"result":true,"status":"ok","time":0.1}"#)
+        .create_async()
+        .await;
+    let info_mock = server
+        .mock("GET", "/collections/paris")
+        .with_status(200)
+        .with_body(r#"{"result":{"status":"green","optimizer_status":"ok","indexed_vectors_count":0,"points_count":42,"segments_count":2,"config":{"params":{"vectors":{"size":768,"distance":"Cosine"}}},"payload_schema":{}},"status":"ok","time":0.001}"#)
+        .create_async()
+        .await;
+    let delete_mock = server
+        .mock("DELETE", "/collections/paris")
+        .with_status(200)
+        .with_body(r#"{"result":true,"status":"ok","time":0.1}"#)
+        .create_async()
+        .await;
+
+    let config = RagVdbConfig {
+        server_url: server.url(),
+        collection_name: vec!["paris".to_string()],
+        limit: vec![5],
+        score_threshold: vec![0.5],
+        api_key: Some("secret".to_string()),
+    };
+    let vdb = VectorDbClient::from_config(&config).unwrap();
+
+    assert_eq!(
+        vdb.list_collections().await.unwrap(),
+        vec!["default", "paris"]
+    );
+    assert!(vdb
+        .create_collection("paris", VectorParams::new(768, Distance::Cosine))
+        .await
+        .unwrap());
+    let info = vdb.collection_info("paris").await.unwrap();
+    assert_eq!(info.status, "green");
+    assert_eq!(info.points_count, Some(42));
+    assert_eq!(
+        info.vector_params(),
+        Some(VectorParams::new(768, Distance::Cosine))
+    );
+    assert!(vdb.delete_collection("paris").await.unwrap());
+
+    list_mock.assert_async().await;
+    create_mock.assert_async().await;
+    info_mock.assert_async().await;
+    delete_mock.assert_async().await;
+}
+
+#[tokio::test]
+async fn test_count_and_delete_points() {
+    let mut server = mockito::Server::new_async().await;
+    let count_all_mock = server
+        .mock("POST", "/vdb/collections/paris/points/count")
+        .match_body(Matcher::Json(json!({ "exact": true })))
+        .with_status(200)
+        .with_body(r#"{"result":{"count":42},"status":"ok","time":0.001}"#)
+        .create_async()
+        .await;
+    let filter_body = json!({
+        "must": [{ "key": "source", "match": { "value": "Paris is in France." } }],
+        "must_not": [{ "has_id": [1, 2] }],
+    });
+    let count_mock = server
+        .mock("POST", "/vdb/collections/paris/points/count")
+        .match_body(Matcher::Json(
+            json!({ "exact": true, "filter": filter_body }),
+        ))
+        .with_status(200)
+        .with_body(r#"{"result":{"count":3},"status":"ok","time":0.001}"#)
+        .create_async()
+        .await;
+    let delete_mock = server
+        .mock("POST", "/vdb/collections/paris/points/delete")
+        .match_query(Matcher::UrlEncoded("wait".to_string(), "true".to_string()))
+        .match_body(Matcher::Json(json!({ "filter": filter_body })))
+        .with_status(200)
+        .with_body(
+            r#"{"result":{"operation_id":7,"status":"completed"},"status":"ok","time":0.01}"#,
+        )
+        .create_async()
+        .await;
+
+    // the path of the server URL is preserved
+    let vdb = VectorDbClient::new(format!("{}/vdb/", server.url())).unwrap();
+    let filter = Filter::new()
+        .must(Condition::matches("source", "Paris is in France."))
+        .must_not(Condition::has_id([1, 2]));
+
+    assert_eq!(vdb.count_points("paris", None).await.unwrap(), 42);
+    assert_eq!(vdb.count_points("paris", Some(&filter)).await.unwrap(), 3);
+    let result = vdb.delete_points("paris", &filter).await.unwrap();
+    assert_eq!(result.operation_id, Some(7));
+    assert_eq!(result.status, "completed");
+
+    count_all_mock.assert_async().await;
+    count_mock.assert_async().await;
+    delete_mock.assert_async().await;
+}
+
+#[tokio::test]
+async fn test_vdb_errors() {
+    let mut server = mockito::Server::new_async().await;
+    let _mock = server
+        .mock("GET", "/collections/missing%2Fname")
+        .with_status(404)
+        .with_body(r#"{"status":{"error":"Not found: Collection `missing/name` doesn't exist!"},"time":0.001}"#)
+        .create_async()
+        .await;
+
+    let vdb = VectorDbClient::new(server.url()).unwrap();
+
+    // the collection name is kept in a single path segment
+    match vdb.collection_info("missing/name").await {
+        Err(LlamaEdgeError::Http {
+            status, message, ..
+        }) => {
+            assert_eq!(status.as_u16(), 404);
+            assert_eq!(
+                message,
+                "Not found: Collection `missing/name` doesn't exist!"
+            );
+        }
+        other => panic!("unexpected result: {:?}", other),
+    }
+
+    let result = vdb.delete_points("paris", &Filter::new()).await;
+    assert!(matches!(result, Err(LlamaEdgeError::InvalidArgument(_))));
+    let result = vdb.count_points(" ", None).await;
+    assert!(matches!(result, Err(LlamaEdgeError::InvalidArgument(_))));
+    let result = vdb
+        .create_collection("paris", VectorParams::new(0, Distance::Dot))
+        .await;
+    assert!(matches!(result, Err(LlamaEdgeError::InvalidArgument(_))));
+    assert!(VectorDbClient::new("http://localhost:6333?x=1").is_err());
+}
@@ -0,0 +1,187 @@
+#![cfg(feature = "audio")]
+
+use llamaedge::{
+    audio::wav::{self, SampleFormat, WHISPER_SAMPLE_RATE},
+    error::LlamaEdgeError,
+    params::TranscriptionParams,
+    upload::UploadFile,
+    Client,
+};
+use std::{f64::consts::PI, time::Duration};
+
+/// Build a WAV file from the given format and sample data.
+fn build_wav(
+    format_tag: u16,
+    channels: u16,
+    sample_rate: u32,
+    bits_per_sample: u16,
+    data: Vec<u8>,
+) -> Vec<u8> {
+    let block_align = channels * bits_per_sample / 8;
+
+    let mut bytes = Vec::new();
+    bytes.extend_from_slice(b"RIFF");
+    bytes.extend_from_slice(&(36 + data.len() as u32).to_le_bytes());
+    bytes.extend_from_slice(b"WAVE");
+    bytes.extend_from_slice(b"fmt ");
+    bytes.extend_from_slice(&16u32.to_le_bytes());
+    bytes.extend_from_slice(&format_tag.to_le_bytes());
+    bytes.extend_from_slice(&channels.to_le_bytes());
+    bytes.extend_from_slice(&sample_rate.to_le_bytes());
+    bytes.extend_from_slice(&(sample_rate * block_align as u32).to_le_bytes());
+    bytes.extend_from_slice(&block_align.to_le_bytes());
+    bytes.extend_from_slice(&bits_per_sample.to_le_bytes());
+    bytes.extend_from_slice(b"data");
+    bytes.extend_from_slice(&(data.len() as u32).to_le_bytes());
+    bytes.extend_from_slice(&data);
+    bytes
+}
+
+/// Generate one second of a 440 Hz sine with an amplitude of 0.5.
+fn sine(sample_rate: u32) -> impl Iterator<Item = f64> {
+    (0..sample_rate).map(move |n| 0.5 * (2.0 * PI * 440.0 * n as f64 / sample_rate as f64).sin())
+}
+
+/// Decode the samples of a 16-bit mono WAV file produced by `to_whisper_wav`.
+fn pcm16_samples(bytes: &[u8]) -> Vec<f64> {
+    bytes[44..]
+        .chunks_exact(2)
+        .map(|b| i16::from_le_bytes([b[0], b[1]]) as f64 / 32768.0)
+        .collect()
+}
+
+#[test]
+fn test_inspect_assets() {
+    for path in ["tests/assets/test.wav", "tests/assets/test_zh.wav"] {
+        let bytes = std::fs::read(path).unwrap();
+        let info = wav::inspect(&bytes).unwrap();
+        assert_eq!(info.sample_format, SampleFormat::Pcm);
+        assert_eq!(info.channels, 1);
+        assert_eq!(info.sample_rate, 16_000);
+        assert_eq!(info.bits_per_sample, 16);
+        assert!(info.duration > Duration::from_secs(4));
+        assert!(info.is_whisper_compatible());
+
+        // files already in the expected format are left unchanged
+        assert_eq!(wav::to_whisper_wav(&bytes).unwrap(), bytes);
+    }
+}
+
+#[test]
+fn test_convert_stereo_44100() {
+    let data = sine(44_100)
+        .flat_map(|sample| {
+            let sample = (sample * 32767.0) as i16;
+            // the right channel is silent, so the mix has half the amplitude
+            [sample.to_le_bytes(), 0i16.to_le_bytes()].concat()
+        })
+        .collect();
+    let input = build_wav(1, 2, 44_100, 16, data);
+    assert!(!wav::inspect(&input).unwrap().is_whisper_compatible());
+
+    let output = wav::to_whisper_wav(&input).unwrap();
+    let info = wav::inspect(&output).unwrap();
+    assert!(info.is_whisper_compatible());
+    assert_eq!(info.frames, WHISPER_SAMPLE_RATE as u64);
+    assert_eq!(info.duration, Duration::from_secs(1));
+
+    let samples = pcm16_samples(&output);
+    let peak = samples.iter().fold(0.0f64, |peak, s| peak.max(s.abs()));
+    assert!((peak - 0.25).abs() < 0.01, "peak: {}", peak);
+
+    // a 440 Hz sine crosses zero about 880 times per second
+    let crossings = samples
+        .windows(2)
+        .filter(|pair| (pair[0] < 0.0) != (pair[1] < 0.0))
+        .count();
+    assert!((870..=890).contains(&crossings), "crossings: {}", crossings);
+}
+
+#[test]
+fn test_convert_float_and_8_bit() {
+    let float = build_wav(
+        3,
+        1,
+        48_000,
+        32,
+        sine(48_000)
+            .flat_map(|sample| (sample as f32).to_le_bytes())
+            .collect(),
+    );
+    let unsigned = build_wav(
+        1,
+        1,
+        8_000,
+        8,
+        sine(8_000)
+            .map(|sample| (sample * 127.0 + 128.0).round() as u8)
+            .collect(),
+    );
+
+    for input in [float, unsigned] {
+        let output = wav::to_whisper_wav(&input).unwrap();
+        let info = wav::inspect(&output).unwrap();
+        assert!(info.is_whisper_compatible());
+        assert_eq!(info.frames, WHISPER_SAMPLE_RATE as u64);
+
+        let samples = pcm16_samples(&output);
+        let peak = samples.iter().fold(0.0f64, |peak, s| peak.max(s.abs()));
+        assert!((peak - 0.5).abs() < 0.02, "peak: {}", peak);
+    }
+}
+
+#[test]
+fn test_invalid_wav() {
+    assert!(matches!(
+        wav::inspect(b"not a wav file"),
+        Err(LlamaEdgeError::InvalidArgument(_))
+    ));
+
+    // A-law samples cannot be decoded
+    let alaw = build_wav(6, 1, 8_000, 8, vec![0; 800]);
+    assert_eq!(
+        wav::inspect(&alaw).unwrap().sample_format,
+        SampleFormat::Other(6)
+    );
+    assert!(matches!(
+        wav::to_whisper_wav(&alaw),
+        Err(LlamaEdgeError::InvalidArgument(_))
+    ));
+}
+
+#[tokio::test]
+async fn test_transcribe_preprocess_audio() {
+    let mut server = mockito::Server::new_async().await;
+    let mock = server
+        .mock("POST", "/v1/audio/transcriptions")
+        .match_request(|request| {
+            // the uploaded file is a 16 kHz mono 16-bit WAV file
+            let body = request.body().unwrap();
+            body.windows(4)
+                .position(|window| window == b"RIFF")
+                .and_then(|start| wav::inspect(&body[start..]).ok())
+                .is_some_and(|info| info.is_whisper_compatible())
+        })
+        .with_status(200)
+        .with_body(r#"{"text":"Hello"}"#)
+        .create_async()
+        .await;
+
+    let data = sine(22_050)
+        .flat_map(|sample| ((sample * 32767.0) as i16).to_le_bytes())
+        .collect();
+    let input = build_wav(1, 1, 22_050, 16, data);
+
+    let client = Client::new(server.url()).unwrap();
+    let params = TranscriptionParams {
+        preprocess_audio: true,
+        ..Default::default()
+    };
+    let transcript = client
+        .transcribe(UploadFile::from_bytes(input, "sine.wav"), "en", params)
+        .await
+        .unwrap();
+
+    assert_eq!(transcript.text(), "Hello");
+    mock.assert_async().await;
+}